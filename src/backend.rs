@@ -0,0 +1,635 @@
+//!
+//! The graphics operations yakui-miniquad needs from a renderer, so that painting can target something other than a live [`miniquad::Context`].
+//!
+//! [`GraphicsBackend`] is implemented for every [`RenderingBackend`], which is what you'll want in your game, and for [`RecordingBackend`],
+//! which keeps track of every operation issued to it so that the paint path can be inspected without a GPU.
+//!
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use miniquad::{
+    BufferLayout, BufferSource, BufferType, BufferUsage, FilterMode, MipmapFilterMode, PassAction,
+    PipelineParams, RenderingBackend, ShaderError, ShaderMeta, ShaderSource, TextureAccess,
+    TextureFormat, TextureParams, TextureSource, TextureWrap, VertexAttribute,
+};
+
+/// The subset of [`RenderingBackend`] used to render yakui.
+///
+/// Handles are associated types rather than the miniquad ones, as those can only be created by miniquad itself.
+pub trait GraphicsBackend {
+    type Buffer: Copy + Eq + Hash + Debug;
+    type Texture: Copy + Eq + Hash + Debug;
+    type Shader: Copy + Eq + Hash + Debug;
+    type Pipeline: Copy + Eq + Hash + Debug;
+    type RenderPass: Copy + PartialEq + Debug;
+
+    fn new_shader(
+        &mut self,
+        shader: ShaderSource,
+        meta: ShaderMeta,
+    ) -> Result<Self::Shader, ShaderError>;
+
+    fn new_pipeline(
+        &mut self,
+        buffer_layout: &[BufferLayout],
+        attributes: &[VertexAttribute],
+        shader: Self::Shader,
+        params: PipelineParams,
+    ) -> Self::Pipeline;
+
+    fn apply_pipeline(&mut self, pipeline: &Self::Pipeline);
+
+    /// Applies the raw bytes of the uniforms of the current pipeline.
+    fn apply_uniforms(&mut self, bytes: &[u8]);
+
+    /// Creates an empty buffer of `size` bytes, made up of elements which are `element_size` bytes each.
+    fn new_buffer(
+        &mut self,
+        buffer_type: BufferType,
+        usage: BufferUsage,
+        size: usize,
+        element_size: usize,
+    ) -> Self::Buffer;
+
+    /// Replaces the start of the buffer with `bytes`, made up of elements which are `element_size` bytes each.
+    fn buffer_update(&mut self, buffer: Self::Buffer, bytes: &[u8], element_size: usize);
+
+    /// Size of the buffer in bytes.
+    fn buffer_size(&mut self, buffer: Self::Buffer) -> usize;
+
+    fn delete_buffer(&mut self, buffer: Self::Buffer);
+
+    fn new_texture(
+        &mut self,
+        access: TextureAccess,
+        source: TextureSource,
+        params: TextureParams,
+    ) -> Self::Texture;
+
+    fn texture_update(&mut self, texture: Self::Texture, bytes: &[u8]);
+
+    fn delete_texture(&mut self, texture: Self::Texture);
+
+    fn texture_set_min_filter(
+        &mut self,
+        texture: Self::Texture,
+        filter: FilterMode,
+        mipmap_filter: MipmapFilterMode,
+    );
+
+    fn texture_set_mag_filter(&mut self, texture: Self::Texture, filter: FilterMode);
+
+    fn texture_set_wrap(
+        &mut self,
+        texture: Self::Texture,
+        wrap_x: TextureWrap,
+        wrap_y: TextureWrap,
+    );
+
+    fn texture_generate_mipmaps(&mut self, texture: Self::Texture);
+
+    /// Returns the parameters of the given texture, as it was created and changed since.
+    fn texture_params(&self, texture: Self::Texture) -> TextureParams;
+
+    /// Creates a render pass drawing into the given color texture, which must have been created as a render target.
+    fn new_render_pass(&mut self, color_texture: Self::Texture) -> Self::RenderPass;
+
+    fn delete_render_pass(&mut self, pass: Self::RenderPass);
+
+    /// Starts rendering into the given render pass, or the default framebuffer if there is none.
+    fn begin_pass(&mut self, pass: Option<Self::RenderPass>, action: PassAction);
+
+    fn end_render_pass(&mut self);
+
+    /// Ends the frame, presenting what was drawn to the default render pass.
+    fn commit_frame(&mut self);
+
+    fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32);
+
+    fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32);
+
+    fn apply_bindings(
+        &mut self,
+        vertex_buffers: &[Self::Buffer],
+        index_buffer: Self::Buffer,
+        textures: &[Self::Texture],
+    );
+
+    fn draw(&mut self, base_element: i32, num_elements: i32, num_instances: i32);
+}
+
+impl<T: RenderingBackend + ?Sized> GraphicsBackend for T {
+    type Buffer = miniquad::BufferId;
+    type Texture = miniquad::TextureId;
+    type Shader = miniquad::ShaderId;
+    type Pipeline = miniquad::Pipeline;
+    type RenderPass = miniquad::RenderPass;
+
+    fn new_shader(
+        &mut self,
+        shader: ShaderSource,
+        meta: ShaderMeta,
+    ) -> Result<Self::Shader, ShaderError> {
+        RenderingBackend::new_shader(self, shader, meta)
+    }
+
+    fn new_pipeline(
+        &mut self,
+        buffer_layout: &[BufferLayout],
+        attributes: &[VertexAttribute],
+        shader: Self::Shader,
+        params: PipelineParams,
+    ) -> Self::Pipeline {
+        RenderingBackend::new_pipeline(self, buffer_layout, attributes, shader, params)
+    }
+
+    fn apply_pipeline(&mut self, pipeline: &Self::Pipeline) {
+        RenderingBackend::apply_pipeline(self, pipeline)
+    }
+
+    fn apply_uniforms(&mut self, bytes: &[u8]) {
+        RenderingBackend::apply_uniforms_from_bytes(self, bytes.as_ptr(), bytes.len())
+    }
+
+    fn new_buffer(
+        &mut self,
+        buffer_type: BufferType,
+        usage: BufferUsage,
+        size: usize,
+        element_size: usize,
+    ) -> Self::Buffer {
+        RenderingBackend::new_buffer(
+            self,
+            buffer_type,
+            usage,
+            BufferSource::Empty { size, element_size },
+        )
+    }
+
+    fn buffer_update(&mut self, buffer: Self::Buffer, bytes: &[u8], element_size: usize) {
+        // SAFETY: the pointer and size both come from the same live slice
+        let source = unsafe { BufferSource::pointer(bytes.as_ptr(), bytes.len(), element_size) };
+        RenderingBackend::buffer_update(self, buffer, source)
+    }
+
+    fn buffer_size(&mut self, buffer: Self::Buffer) -> usize {
+        RenderingBackend::buffer_size(self, buffer)
+    }
+
+    fn delete_buffer(&mut self, buffer: Self::Buffer) {
+        RenderingBackend::delete_buffer(self, buffer)
+    }
+
+    fn new_texture(
+        &mut self,
+        access: TextureAccess,
+        source: TextureSource,
+        params: TextureParams,
+    ) -> Self::Texture {
+        RenderingBackend::new_texture(self, access, source, params)
+    }
+
+    fn texture_update(&mut self, texture: Self::Texture, bytes: &[u8]) {
+        RenderingBackend::texture_update(self, texture, bytes)
+    }
+
+    fn delete_texture(&mut self, texture: Self::Texture) {
+        RenderingBackend::delete_texture(self, texture)
+    }
+
+    fn texture_set_min_filter(
+        &mut self,
+        texture: Self::Texture,
+        filter: FilterMode,
+        mipmap_filter: MipmapFilterMode,
+    ) {
+        RenderingBackend::texture_set_min_filter(self, texture, filter, mipmap_filter)
+    }
+
+    fn texture_set_mag_filter(&mut self, texture: Self::Texture, filter: FilterMode) {
+        RenderingBackend::texture_set_mag_filter(self, texture, filter)
+    }
+
+    fn texture_set_wrap(
+        &mut self,
+        texture: Self::Texture,
+        wrap_x: TextureWrap,
+        wrap_y: TextureWrap,
+    ) {
+        RenderingBackend::texture_set_wrap(self, texture, wrap_x, wrap_y)
+    }
+
+    fn texture_generate_mipmaps(&mut self, texture: Self::Texture) {
+        RenderingBackend::texture_generate_mipmaps(self, texture)
+    }
+
+    fn texture_params(&self, texture: Self::Texture) -> TextureParams {
+        RenderingBackend::texture_params(self, texture)
+    }
+
+    fn new_render_pass(&mut self, color_texture: Self::Texture) -> Self::RenderPass {
+        RenderingBackend::new_render_pass(self, color_texture, None)
+    }
+
+    fn delete_render_pass(&mut self, pass: Self::RenderPass) {
+        RenderingBackend::delete_render_pass(self, pass)
+    }
+
+    fn begin_pass(&mut self, pass: Option<Self::RenderPass>, action: PassAction) {
+        RenderingBackend::begin_pass(self, pass, action)
+    }
+
+    fn end_render_pass(&mut self) {
+        RenderingBackend::end_render_pass(self)
+    }
+
+    fn commit_frame(&mut self) {
+        RenderingBackend::commit_frame(self)
+    }
+
+    fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        RenderingBackend::apply_viewport(self, x, y, w, h)
+    }
+
+    fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        RenderingBackend::apply_scissor_rect(self, x, y, w, h)
+    }
+
+    fn apply_bindings(
+        &mut self,
+        vertex_buffers: &[Self::Buffer],
+        index_buffer: Self::Buffer,
+        textures: &[Self::Texture],
+    ) {
+        RenderingBackend::apply_bindings_from_slice(self, vertex_buffers, index_buffer, textures)
+    }
+
+    fn draw(&mut self, base_element: i32, num_elements: i32, num_instances: i32) {
+        RenderingBackend::draw(self, base_element, num_elements, num_instances)
+    }
+}
+
+/// Vertex or index data which can be uploaded as the bytes it's made up of.
+///
+/// # Safety
+///
+/// The type must not contain padding, so every one of its bytes is initialized.
+pub(crate) unsafe trait PlainData: Copy {}
+
+// SAFETY: integers have no padding
+unsafe impl PlainData for u16 {}
+
+/// Reinterprets a slice of plain vertex or index data as the bytes it's made up of.
+pub(crate) fn as_bytes<T: PlainData>(data: &[T]) -> &[u8] {
+    // SAFETY: `PlainData` guarantees there is no padding, so every byte is initialized
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
+}
+
+/// An operation issued to a [`RecordingBackend`].
+///
+/// Resources are identified by the order they were created in, counted separately for each kind of resource.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedCommand {
+    NewShader {
+        shader: usize,
+        images: Vec<String>,
+    },
+    NewPipeline {
+        pipeline: usize,
+        shader: usize,
+        params: PipelineParams,
+    },
+    ApplyPipeline(usize),
+    ApplyUniforms(Vec<u8>),
+    NewBuffer {
+        buffer: usize,
+        buffer_type: BufferType,
+        size: usize,
+    },
+    BufferUpdate {
+        buffer: usize,
+        size: usize,
+    },
+    DeleteBuffer(usize),
+    NewTexture {
+        texture: usize,
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+    },
+    TextureUpdate(usize),
+    DeleteTexture(usize),
+    TextureSetMinFilter {
+        texture: usize,
+        filter: FilterMode,
+        mipmap_filter: MipmapFilterMode,
+    },
+    TextureSetMagFilter {
+        texture: usize,
+        filter: FilterMode,
+    },
+    TextureSetWrap {
+        texture: usize,
+        wrap_x: TextureWrap,
+        wrap_y: TextureWrap,
+    },
+    TextureGenerateMipmaps(usize),
+    NewRenderPass {
+        pass: usize,
+        color_texture: usize,
+    },
+    DeleteRenderPass(usize),
+    BeginPass {
+        pass: Option<usize>,
+        clear_color: Option<(f32, f32, f32, f32)>,
+    },
+    EndRenderPass,
+    CommitFrame,
+    ApplyViewport {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+    },
+    ApplyScissorRect {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+    },
+    ApplyBindings {
+        vertex_buffers: Vec<usize>,
+        index_buffer: usize,
+        textures: Vec<usize>,
+    },
+    Draw {
+        base_element: i32,
+        num_elements: i32,
+        num_instances: i32,
+    },
+}
+
+/// A [`GraphicsBackend`] which renders nothing, but records every command issued to it along with the contents of its buffers.
+///
+/// Useful for testing how a UI gets painted on machines without a GPU.
+#[derive(Debug, Default)]
+pub struct RecordingBackend {
+    commands: Vec<RecordedCommand>,
+    buffers: Vec<Vec<u8>>,
+    shaders: usize,
+    pipelines: usize,
+    textures: Vec<TextureParams>,
+    render_passes: usize,
+}
+
+impl RecordingBackend {
+    pub fn new() -> RecordingBackend {
+        RecordingBackend::default()
+    }
+
+    /// Returns every command recorded so far, in the order they were issued.
+    pub fn commands(&self) -> &[RecordedCommand] {
+        &self.commands
+    }
+
+    /// Removes and returns every command recorded so far, resources created before stay valid.
+    pub fn take_commands(&mut self) -> Vec<RecordedCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Returns the current contents of the given buffer.
+    pub fn buffer_data(&self, buffer: usize) -> &[u8] {
+        &self.buffers[buffer]
+    }
+}
+
+impl GraphicsBackend for RecordingBackend {
+    type Buffer = usize;
+    type Texture = usize;
+    type Shader = usize;
+    type Pipeline = usize;
+    type RenderPass = usize;
+
+    fn new_shader(
+        &mut self,
+        _shader: ShaderSource,
+        meta: ShaderMeta,
+    ) -> Result<Self::Shader, ShaderError> {
+        let shader = self.shaders;
+        self.shaders += 1;
+        self.commands.push(RecordedCommand::NewShader {
+            shader,
+            images: meta.images,
+        });
+        Ok(shader)
+    }
+
+    fn new_pipeline(
+        &mut self,
+        _buffer_layout: &[BufferLayout],
+        _attributes: &[VertexAttribute],
+        shader: Self::Shader,
+        params: PipelineParams,
+    ) -> Self::Pipeline {
+        let pipeline = self.pipelines;
+        self.pipelines += 1;
+        self.commands.push(RecordedCommand::NewPipeline {
+            pipeline,
+            shader,
+            params,
+        });
+        pipeline
+    }
+
+    fn apply_pipeline(&mut self, pipeline: &Self::Pipeline) {
+        self.commands
+            .push(RecordedCommand::ApplyPipeline(*pipeline));
+    }
+
+    fn apply_uniforms(&mut self, bytes: &[u8]) {
+        self.commands
+            .push(RecordedCommand::ApplyUniforms(bytes.to_vec()));
+    }
+
+    fn new_buffer(
+        &mut self,
+        buffer_type: BufferType,
+        _usage: BufferUsage,
+        size: usize,
+        _element_size: usize,
+    ) -> Self::Buffer {
+        let buffer = self.buffers.len();
+        self.buffers.push(vec![0; size]);
+        self.commands.push(RecordedCommand::NewBuffer {
+            buffer,
+            buffer_type,
+            size,
+        });
+        buffer
+    }
+
+    fn buffer_update(&mut self, buffer: Self::Buffer, bytes: &[u8], _element_size: usize) {
+        let data = &mut self.buffers[buffer];
+        assert!(
+            bytes.len() <= data.len(),
+            "[yakui-miniquad]: buffer {} updated with {} bytes, but only has room for {}",
+            buffer,
+            bytes.len(),
+            data.len()
+        );
+        data[..bytes.len()].copy_from_slice(bytes);
+        self.commands.push(RecordedCommand::BufferUpdate {
+            buffer,
+            size: bytes.len(),
+        });
+    }
+
+    fn buffer_size(&mut self, buffer: Self::Buffer) -> usize {
+        self.buffers[buffer].len()
+    }
+
+    fn delete_buffer(&mut self, buffer: Self::Buffer) {
+        self.buffers[buffer] = Vec::new();
+        self.commands.push(RecordedCommand::DeleteBuffer(buffer));
+    }
+
+    fn new_texture(
+        &mut self,
+        _access: TextureAccess,
+        _source: TextureSource,
+        params: TextureParams,
+    ) -> Self::Texture {
+        let texture = self.textures.len();
+        self.textures.push(params);
+        self.commands.push(RecordedCommand::NewTexture {
+            texture,
+            format: params.format,
+            width: params.width,
+            height: params.height,
+        });
+        texture
+    }
+
+    fn texture_update(&mut self, texture: Self::Texture, bytes: &[u8]) {
+        // miniquad asserts this too, rather than uploading garbage
+        let params = self.textures[texture];
+        assert_eq!(
+            bytes.len(),
+            params.format.size(params.width, params.height) as usize,
+            "texture {texture} updated with the wrong amount of bytes"
+        );
+        self.commands.push(RecordedCommand::TextureUpdate(texture));
+    }
+
+    fn delete_texture(&mut self, texture: Self::Texture) {
+        self.commands.push(RecordedCommand::DeleteTexture(texture));
+    }
+
+    fn texture_set_min_filter(
+        &mut self,
+        texture: Self::Texture,
+        filter: FilterMode,
+        mipmap_filter: MipmapFilterMode,
+    ) {
+        self.textures[texture].min_filter = filter;
+        self.textures[texture].mipmap_filter = mipmap_filter;
+        self.commands.push(RecordedCommand::TextureSetMinFilter {
+            texture,
+            filter,
+            mipmap_filter,
+        });
+    }
+
+    fn texture_set_mag_filter(&mut self, texture: Self::Texture, filter: FilterMode) {
+        self.textures[texture].mag_filter = filter;
+        self.commands
+            .push(RecordedCommand::TextureSetMagFilter { texture, filter });
+    }
+
+    fn texture_set_wrap(
+        &mut self,
+        texture: Self::Texture,
+        wrap_x: TextureWrap,
+        wrap_y: TextureWrap,
+    ) {
+        // miniquad's texture params only hold a single wrap mode for both axes
+        self.textures[texture].wrap = wrap_x;
+        self.commands.push(RecordedCommand::TextureSetWrap {
+            texture,
+            wrap_x,
+            wrap_y,
+        });
+    }
+
+    fn texture_generate_mipmaps(&mut self, texture: Self::Texture) {
+        self.commands
+            .push(RecordedCommand::TextureGenerateMipmaps(texture));
+    }
+
+    fn texture_params(&self, texture: Self::Texture) -> TextureParams {
+        self.textures[texture]
+    }
+
+    fn new_render_pass(&mut self, color_texture: Self::Texture) -> Self::RenderPass {
+        let pass = self.render_passes;
+        self.render_passes += 1;
+        self.commands.push(RecordedCommand::NewRenderPass {
+            pass,
+            color_texture,
+        });
+        pass
+    }
+
+    fn delete_render_pass(&mut self, pass: Self::RenderPass) {
+        self.commands.push(RecordedCommand::DeleteRenderPass(pass));
+    }
+
+    fn begin_pass(&mut self, pass: Option<Self::RenderPass>, action: PassAction) {
+        let clear_color = match action {
+            PassAction::Clear { color, .. } => color,
+            PassAction::Nothing => None,
+        };
+        self.commands
+            .push(RecordedCommand::BeginPass { pass, clear_color });
+    }
+
+    fn end_render_pass(&mut self) {
+        self.commands.push(RecordedCommand::EndRenderPass);
+    }
+
+    fn commit_frame(&mut self) {
+        self.commands.push(RecordedCommand::CommitFrame);
+    }
+
+    fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.commands
+            .push(RecordedCommand::ApplyViewport { x, y, w, h });
+    }
+
+    fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.commands
+            .push(RecordedCommand::ApplyScissorRect { x, y, w, h });
+    }
+
+    fn apply_bindings(
+        &mut self,
+        vertex_buffers: &[Self::Buffer],
+        index_buffer: Self::Buffer,
+        textures: &[Self::Texture],
+    ) {
+        self.commands.push(RecordedCommand::ApplyBindings {
+            vertex_buffers: vertex_buffers.to_vec(),
+            index_buffer,
+            textures: textures.to_vec(),
+        });
+    }
+
+    fn draw(&mut self, base_element: i32, num_elements: i32, num_instances: i32) {
+        self.commands.push(RecordedCommand::Draw {
+            base_element,
+            num_elements,
+            num_instances,
+        });
+    }
+}
@@ -0,0 +1,143 @@
+//!
+//! The GPU buffers the geometry of each frame is streamed into, and how they're grown, shrunk and rotated.
+//!
+
+use miniquad::{BufferType, BufferUsage};
+
+use crate::backend::GraphicsBackend;
+
+/// How the vertex and index buffers yakui is uploaded into are sized and reused, see
+/// [`YakuiMiniquadState::set_buffer_policy`](crate::YakuiMiniquadState::set_buffer_policy).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BufferPolicy {
+    /// How many times the needed size a buffer is made when it has to grow, so a slowly growing ui doesn't recreate
+    /// its buffers every frame. Values below 1 are treated as 1.
+    pub growth_factor: f32,
+
+    /// After how many frames in a row of using less than a quarter of a buffer it's shrunk, or `None` to never shrink.
+    ///
+    /// Buffers which aren't used at all anymore, for example after a huge frame needed more than one batch, are
+    /// deleted after the same number of frames.
+    pub shrink_after_frames: Option<u32>,
+
+    /// How many sets of buffers frames rotate between, so the GPU can still be drawing from the buffers of a previous
+    /// frame while the next one is uploaded, instead of stalling until it's done. At least one set is always used.
+    pub frames_in_flight: usize,
+}
+
+impl Default for BufferPolicy {
+    fn default() -> Self {
+        BufferPolicy {
+            growth_factor: 1.5,
+            shrink_after_frames: Some(300),
+            frames_in_flight: 1,
+        }
+    }
+}
+
+/// A GPU buffer which is replaced by a bigger or smaller one as the amount of data streamed into it changes.
+pub(crate) struct StreamBuffer<Buffer> {
+    buffer_type: BufferType,
+    element_size: usize,
+    buffer: Option<Buffer>,
+    capacity: usize,
+    /// The number of bytes uploaded last.
+    uploaded: usize,
+    /// The number of frames in a row which used less than a quarter of the buffer.
+    underused_frames: u32,
+}
+
+impl<Buffer: Copy> StreamBuffer<Buffer> {
+    pub(crate) fn new(buffer_type: BufferType, element_size: usize) -> Self {
+        StreamBuffer {
+            buffer_type,
+            element_size,
+            buffer: None,
+            capacity: 0,
+            uploaded: 0,
+            underused_frames: 0,
+        }
+    }
+
+    pub(crate) fn buffer(&self) -> Option<Buffer> {
+        self.buffer
+    }
+
+    /// Replaces the start of the buffer with `bytes`, first resizing the buffer if the policy asks for it.
+    ///
+    /// Uploading nothing counts as a frame not using the buffer at all, which deletes it once it's been unused for long enough.
+    pub(crate) fn upload<B: GraphicsBackend<Buffer = Buffer> + ?Sized>(
+        &mut self,
+        ctx: &mut B,
+        bytes: &[u8],
+        policy: &BufferPolicy,
+    ) {
+        let needed = bytes.len();
+        let growth_factor = policy.growth_factor.max(1.0);
+
+        if needed > self.capacity {
+            let grown = (self.capacity as f32 * growth_factor) as usize;
+            self.resize(ctx, needed.max(grown));
+        } else if needed * 4 < self.capacity {
+            self.underused_frames += 1;
+            if policy
+                .shrink_after_frames
+                .is_some_and(|frames| self.underused_frames >= frames)
+            {
+                self.resize(ctx, (needed as f32 * growth_factor) as usize);
+            }
+        } else {
+            self.underused_frames = 0;
+        }
+
+        if let Some(buffer) = self.buffer {
+            if !bytes.is_empty() {
+                ctx.buffer_update(buffer, bytes, self.element_size);
+            }
+        }
+        self.uploaded = needed;
+    }
+
+    /// Returns true if uploading the same data as last time would shrink the buffer.
+    pub(crate) fn wants_shrink(&self, policy: &BufferPolicy) -> bool {
+        self.is_underused()
+            && policy
+                .shrink_after_frames
+                .is_some_and(|frames| self.underused_frames + 1 >= frames)
+    }
+
+    /// Counts a frame drawing the data uploaded last time again, without uploading it.
+    pub(crate) fn reuse(&mut self) {
+        if self.is_underused() {
+            self.underused_frames += 1;
+        }
+    }
+
+    fn is_underused(&self) -> bool {
+        self.uploaded * 4 < self.capacity
+    }
+
+    /// Replaces the buffer with one of at least `size` bytes, or deletes it if `size` is 0.
+    fn resize<B: GraphicsBackend<Buffer = Buffer> + ?Sized>(&mut self, ctx: &mut B, size: usize) {
+        self.delete(ctx);
+
+        if size > 0 {
+            let size = size.div_ceil(self.element_size) * self.element_size;
+            self.buffer = Some(ctx.new_buffer(
+                self.buffer_type,
+                BufferUsage::Stream,
+                size,
+                self.element_size,
+            ));
+            self.capacity = size;
+        }
+    }
+
+    pub(crate) fn delete<B: GraphicsBackend<Buffer = Buffer> + ?Sized>(&mut self, ctx: &mut B) {
+        if let Some(buffer) = self.buffer.take() {
+            ctx.delete_buffer(buffer);
+        }
+        self.capacity = 0;
+        self.underused_frames = 0;
+    }
+}
@@ -0,0 +1,101 @@
+//!
+//! The coordinate spaces yakui-miniquad converts between.
+//!
+//! miniquad reports window sizes and input positions in physical pixels of the framebuffer, with the origin in the top-left.
+//! yakui lays the ui out in logical pixels, which are physical pixels divided by the scale factor, but is given sizes and
+//! positions in physical pixels and hands clip rects back in them too. Scissor rects are finally given to miniquad in
+//! physical pixels of the render target, with the bottom-left origin of OpenGL.
+//!
+
+use miniquad::window::{dpi_scale, high_dpi, screen_size};
+use yakui_core::geometry::{Rect, UVec2, Vec2};
+use yakui_core::Yakui;
+
+/// The physical size of a surface along with how many physical pixels make up a logical one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinateSpace {
+    /// The size of the surface in physical pixels.
+    pub physical_size: Vec2,
+
+    /// The number of physical pixels per logical pixel.
+    pub scale_factor: f32,
+}
+
+impl CoordinateSpace {
+    pub fn new(physical_size: Vec2, scale_factor: f32) -> CoordinateSpace {
+        CoordinateSpace {
+            physical_size,
+            scale_factor,
+        }
+    }
+
+    /// Returns the coordinate space of the miniquad window.
+    ///
+    /// The dpi scale is only used in high-dpi mode, as the framebuffer is otherwise already scaled up by the platform.
+    pub fn of_window() -> CoordinateSpace {
+        let (screen_w, screen_h) = screen_size();
+        let scale_factor = if high_dpi() { dpi_scale() } else { 1.0 };
+        CoordinateSpace::new(Vec2::new(screen_w, screen_h), scale_factor)
+    }
+
+    /// Returns the size of the surface in logical pixels.
+    pub fn logical_size(&self) -> Vec2 {
+        self.physical_size / self.scale_factor
+    }
+
+    /// Converts a position or distance in physical pixels into logical pixels.
+    pub fn to_logical(&self, physical: Vec2) -> Vec2 {
+        physical / self.scale_factor
+    }
+
+    /// Converts a position or distance in logical pixels into physical pixels.
+    pub fn to_physical(&self, logical: Vec2) -> Vec2 {
+        logical * self.scale_factor
+    }
+
+    /// Makes yakui lay the ui out over the whole surface in this coordinate space.
+    pub fn apply(&self, ui: &mut Yakui) {
+        self.apply_to_viewport(ui, Rect::from_pos_size(Vec2::ZERO, self.physical_size));
+    }
+
+    /// Makes yakui lay the ui out inside the given viewport of the surface, in physical pixels with a top-left origin.
+    ///
+    /// yakui then offsets everything it draws by the position of the viewport, and expects input positions relative
+    /// to the surface rather than the viewport.
+    pub fn apply_to_viewport(&self, ui: &mut Yakui, viewport: Rect) {
+        ui.set_scale_factor(self.scale_factor);
+        ui.set_surface_size(self.physical_size);
+        ui.set_unscaled_viewport(viewport);
+    }
+}
+
+/// Converts a clip rect into a scissor rect `(x, y, w, h)` for a render target of the given size.
+///
+/// The clip rect is in physical pixels of a surface of `surface_size` with a top-left origin, and gets scaled to the
+/// render target if it's of a different size. Note that yakui hands out clip rects relative to its viewport, so they
+/// need to be offset by its position first. Returns `None` if nothing inside the clip rect would be visible.
+pub fn clip_to_scissor(
+    clip: Rect,
+    surface_size: Vec2,
+    target_size: UVec2,
+) -> Option<(i32, i32, i32, i32)> {
+    let scale = target_size.as_vec2() / surface_size;
+    let min = (clip.pos() * scale).round().max(Vec2::ZERO).as_uvec2();
+    let max = (clip.max() * scale)
+        .round()
+        .max(Vec2::ZERO)
+        .as_uvec2()
+        .min(target_size);
+
+    if max.x <= min.x || max.y <= min.y {
+        return None;
+    }
+
+    let size = max - min;
+    Some((
+        min.x as i32,
+        (target_size.y - max.y) as i32,
+        size.x as i32,
+        size.y as i32,
+    ))
+}
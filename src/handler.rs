@@ -0,0 +1,227 @@
+//!
+//! An [`EventHandler`] adapter which runs yakui around your own handler, so you don't have to forward every event yourself.
+//!
+//! ```no_run
+//! use miniquad::window::new_rendering_backend;
+//! use miniquad::*;
+//!
+//! use yakui_miniquad::handler::{YakuiEventHandler, YakuiHandler};
+//!
+//! struct Stage {
+//!     ctx: Box<Context>,
+//! }
+//!
+//! impl EventHandler for Stage {
+//!     fn update(&mut self) {
+//!         // yakui is bound to the current thread here, so you can build your ui
+//!         yakui::center(|| {
+//!             yakui::text(32.0, "hello, world!");
+//!         });
+//!     }
+//!
+//!     fn draw(&mut self) {
+//!         self.ctx.begin_default_pass(Default::default());
+//!         // ... draw your game, the ui gets drawn on top afterwards
+//!         self.ctx.end_render_pass();
+//!     }
+//!
+//!     fn mouse_button_down_event(&mut self, _button: MouseButton, _x: f32, _y: f32) {
+//!         // only called for clicks which yakui didn't sink
+//!     }
+//! }
+//!
+//! impl YakuiHandler for Stage {
+//!     fn context(&mut self) -> &mut Context {
+//!         &mut *self.ctx
+//!     }
+//! }
+//!
+//! fn main() {
+//!     miniquad::start(conf::Conf::default(), || {
+//!         Box::new(YakuiEventHandler::new(Stage {
+//!             ctx: new_rendering_backend(),
+//!         }))
+//!     });
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use miniquad::{Context, EventHandler, KeyCode, KeyMods, MouseButton, PassAction, TouchPhase};
+
+use crate::backend::GraphicsBackend;
+use crate::YakuiMiniQuad;
+
+/// An [`EventHandler`] which can be wrapped by [`YakuiEventHandler`].
+pub trait YakuiHandler<B: GraphicsBackend + ?Sized = Context>: EventHandler {
+    /// Returns the rendering context your handler draws with, which yakui is drawn with too.
+    fn context(&mut self) -> &mut B;
+}
+
+/// Wraps your [`EventHandler`], passing every event to yakui first and only forwarding the ones yakui didn't sink.
+/// Presses and scrolls over a yakui widget aren't forwarded either, even if the widget didn't sink them.
+///
+/// Your handler's `update` is called between [`YakuiMiniQuad::start`] and [`YakuiMiniQuad::finish`], so it can build the ui.
+/// After your handler's `draw` the ui is drawn on top in the default render pass and the frame is committed, so your
+/// handler should not call `commit_frame` itself.
+///
+/// Releases of buttons, keys and touches are forwarded exactly when their press was, so your handler never sees a release
+/// without a press, and presses it saw can't get stuck.
+pub struct YakuiEventHandler<H: YakuiHandler<B>, B: GraphicsBackend + ?Sized = Context> {
+    yakui_mq: YakuiMiniQuad<B>,
+    handler: H,
+    forwarded_buttons: HashSet<MouseButton>,
+    forwarded_keys: HashSet<KeyCode>,
+    forwarded_touches: HashSet<u64>,
+}
+
+impl<H: YakuiHandler<B>, B: GraphicsBackend + ?Sized> YakuiEventHandler<H, B> {
+    pub fn new(mut handler: H) -> YakuiEventHandler<H, B> {
+        YakuiEventHandler {
+            yakui_mq: YakuiMiniQuad::new(handler.context()),
+            handler,
+            forwarded_buttons: HashSet::new(),
+            forwarded_keys: HashSet::new(),
+            forwarded_touches: HashSet::new(),
+        }
+    }
+
+    /// Returns a reference to the wrapped yakui integration.
+    pub fn yakui(&mut self) -> &mut YakuiMiniQuad<B> {
+        &mut self.yakui_mq
+    }
+
+    /// Returns a reference to your handler.
+    pub fn handler(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    /// Returns your handler, dropping the yakui integration.
+    pub fn into_handler(self) -> H {
+        self.handler
+    }
+
+    fn forget_forwarded_input(&mut self) {
+        self.forwarded_buttons.clear();
+        self.forwarded_keys.clear();
+        self.forwarded_touches.clear();
+    }
+}
+
+impl<H: YakuiHandler<B>, B: GraphicsBackend + ?Sized> EventHandler for YakuiEventHandler<H, B> {
+    fn update(&mut self) {
+        self.yakui_mq.start();
+        self.handler.update();
+        self.yakui_mq.finish();
+    }
+
+    fn draw(&mut self) {
+        self.handler.draw();
+
+        let ctx = self.handler.context();
+        ctx.begin_pass(None, PassAction::Nothing);
+        self.yakui_mq.draw(ctx);
+        ctx.end_render_pass();
+
+        ctx.commit_frame();
+    }
+
+    fn resize_event(&mut self, width: f32, height: f32) {
+        self.yakui_mq.resize_event(width, height);
+        self.handler.resize_event(width, height);
+    }
+
+    fn mouse_motion_event(&mut self, x: f32, y: f32) {
+        // motion is never sunk, and your game still wants to know where the cursor is
+        self.yakui_mq.mouse_motion_event(x, y);
+        self.handler.mouse_motion_event(x, y);
+    }
+
+    fn mouse_wheel_event(&mut self, x: f32, y: f32) {
+        let sunk = self.yakui_mq.mouse_wheel_event(x, y);
+        if !sunk && !self.yakui_mq.is_cursor_over_ui() {
+            self.handler.mouse_wheel_event(x, y);
+        }
+    }
+
+    fn mouse_button_down_event(&mut self, button: MouseButton, x: f32, y: f32) {
+        let sunk = self.yakui_mq.mouse_button_down_event(button, x, y);
+        if !sunk && !self.yakui_mq.is_cursor_over_ui() {
+            self.forwarded_buttons.insert(button);
+            self.handler.mouse_button_down_event(button, x, y);
+        }
+    }
+
+    fn mouse_button_up_event(&mut self, button: MouseButton, x: f32, y: f32) {
+        self.yakui_mq.mouse_button_up_event(button, x, y);
+        if self.forwarded_buttons.remove(&button) {
+            self.handler.mouse_button_up_event(button, x, y);
+        }
+    }
+
+    fn char_event(&mut self, character: char, keymods: KeyMods, repeat: bool) {
+        if !self.yakui_mq.char_event(character, keymods, repeat) {
+            self.handler.char_event(character, keymods, repeat);
+        }
+    }
+
+    fn key_down_event(&mut self, keycode: KeyCode, keymods: KeyMods, repeat: bool) {
+        if !self.yakui_mq.key_down_event(keycode, keymods, repeat) {
+            self.forwarded_keys.insert(keycode);
+            self.handler.key_down_event(keycode, keymods, repeat);
+        }
+    }
+
+    fn key_up_event(&mut self, keycode: KeyCode, keymods: KeyMods) {
+        self.yakui_mq.key_up_event(keycode, keymods);
+        if self.forwarded_keys.remove(&keycode) {
+            self.handler.key_up_event(keycode, keymods);
+        }
+    }
+
+    fn touch_event(&mut self, phase: TouchPhase, id: u64, x: f32, y: f32) {
+        let sunk = self.yakui_mq.touch_event(phase, id, x, y);
+
+        let forward = match phase {
+            TouchPhase::Started => {
+                let forward = !sunk && !self.yakui_mq.is_cursor_over_ui();
+                if forward {
+                    self.forwarded_touches.insert(id);
+                }
+                forward
+            }
+            TouchPhase::Moved => self.forwarded_touches.contains(&id),
+            TouchPhase::Ended | TouchPhase::Cancelled => self.forwarded_touches.remove(&id),
+        };
+
+        if forward {
+            self.handler.touch_event(phase, id, x, y);
+        }
+    }
+
+    fn raw_mouse_motion(&mut self, dx: f32, dy: f32) {
+        self.handler.raw_mouse_motion(dx, dy);
+    }
+
+    fn window_minimized_event(&mut self) {
+        self.yakui_mq.window_minimized_event();
+        self.forget_forwarded_input();
+        self.handler.window_minimized_event();
+    }
+
+    fn window_restored_event(&mut self) {
+        self.yakui_mq.window_restored_event();
+        self.forget_forwarded_input();
+        self.handler.window_restored_event();
+    }
+
+    fn quit_requested_event(&mut self) {
+        self.yakui_mq.quit_requested_event();
+        self.forget_forwarded_input();
+        self.handler.quit_requested_event();
+    }
+
+    fn files_dropped_event(&mut self) {
+        self.handler.files_dropped_event();
+    }
+}
@@ -0,0 +1,36 @@
+//!
+//! What yakui did with the input it was given each frame, so that your game only handles the input yakui didn't want.
+//!
+
+use miniquad::{KeyCode, MouseButton, TouchPhase};
+
+/// A snapshot of which input yakui wants, taken by [`YakuiMiniQuad::finish`](crate::YakuiMiniQuad::finish).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InputCapture {
+    /// The cursor is over a widget interested in the mouse, or a mouse button pressed on one is still held down.
+    pub wants_pointer: bool,
+
+    /// A widget taking keyboard input, such as a text box, was focused by clicking it, and nothing was clicked outside
+    /// of the ui since.
+    pub wants_keyboard: bool,
+
+    /// The events yakui sunk since the previous frame, in the order they were handled.
+    pub consumed_events: Vec<ConsumedEvent>,
+}
+
+impl InputCapture {
+    /// Returns true if yakui sunk the given event since the previous frame.
+    pub fn consumed(&self, event: ConsumedEvent) -> bool {
+        self.consumed_events.contains(&event)
+    }
+}
+
+/// An input event sunk by yakui, described with the miniquad types it was received as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsumedEvent {
+    MouseButton { button: MouseButton, down: bool },
+    MouseWheel { x: f32, y: f32 },
+    Key { keycode: KeyCode, down: bool },
+    Char(char),
+    Touch { id: u64, phase: TouchPhase },
+}
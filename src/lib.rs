@@ -95,6 +95,18 @@
 //!     fn key_up_event(&mut self, keycode: KeyCode, keymods: KeyMods) {
 //!         self.yakui_mq.key_up_event(keycode, keymods);
 //!     }
+//!
+//!     fn touch_event(&mut self, phase: TouchPhase, id: u64, x: f32, y: f32) {
+//!         self.yakui_mq.touch_event(phase, id, x, y);
+//!     }
+//!
+//!     fn window_minimized_event(&mut self) {
+//!         self.yakui_mq.window_minimized_event();
+//!     }
+//!
+//!     fn window_restored_event(&mut self) {
+//!         self.yakui_mq.window_restored_event();
+//!     }
 //! }
 //!
 //! fn main() {
@@ -102,52 +114,149 @@
 //!         Box::new(Stage::new(new_rendering_backend()))
 //!     });
 //! }
-//!```
+//! ```
+//!
+//! If you'd rather not forward every event yourself, wrap your handler in a [`handler::YakuiEventHandler`] instead,
+//! which also only passes on the events yakui didn't sink.
 
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::mem::size_of;
-use std::{collections::HashMap, ops::Range};
+use std::ops::{Range, RangeInclusive};
 
-use miniquad::window::{dpi_scale, screen_size};
 use miniquad::{
-    Bindings, BlendFactor, BlendState, BlendValue, BufferId, BufferLayout, BufferSource,
-    BufferType, BufferUsage, Comparison, Context, CullFace, Equation, EventHandler, FilterMode,
-    FrontFaceOrder, KeyCode, KeyMods, MipmapFilterMode, MouseButton, Pipeline, PipelineParams,
-    PrimitiveType, ShaderSource, TextureAccess, TextureFormat, TextureId, TextureKind,
-    TextureParams, TextureSource, TextureWrap, VertexAttribute, VertexFormat,
+    BlendFactor, BlendState, BlendValue, BufferLayout, BufferType, Comparison, Context, CullFace,
+    Equation, FilterMode, FrontFaceOrder, KeyCode, KeyMods, MipmapFilterMode, MouseButton,
+    PassAction, PipelineParams, PrimitiveType, ShaderSource, TextureAccess, TextureFormat,
+    TextureKind, TextureParams, TextureSource, TextureWrap, TouchPhase, VertexAttribute,
+    VertexFormat,
 };
-use yakui_core::geometry::Rect;
+use yakui_core::event::{Event, EventInterest};
+use yakui_core::geometry::{Color, Rect, UVec2};
 use yakui_core::input::KeyCode as YakuiKeyCode;
+use yakui_core::input::Modifiers;
 use yakui_core::input::MouseButton as YakuiMouseButton;
-use yakui_core::{event::Event, paint::PaintDom, Yakui};
+use yakui_core::{paint::PaintDom, WidgetId, Yakui};
 
 pub use miniquad;
 pub use yakui_core;
 
+pub mod backend;
+pub mod buffers;
+pub mod coordinates;
+pub mod handler;
+pub mod input;
+pub mod renderer;
+#[cfg(feature = "software-rasterizer")]
+pub mod software;
+pub mod stack;
+
+use backend::{as_bytes, GraphicsBackend, PlainData};
+use buffers::{BufferPolicy, StreamBuffer};
+use coordinates::{clip_to_scissor, CoordinateSpace};
+use input::{ConsumedEvent, InputCapture};
+use renderer::YakuiRenderer;
+
 #[repr(C)]
+#[derive(Clone, Copy)]
 struct YakuiVertex {
     pos: yakui_core::geometry::Vec2,
     texcoord: yakui_core::geometry::Vec2,
     color: yakui_core::geometry::Vec4,
 }
 
-pub struct YakuiMiniQuad {
+// SAFETY: made up of f32s only, so there is no padding
+unsafe impl PlainData for YakuiVertex {}
+
+/// A vertex in [`VertexLayout::Compact`], with its color encoded as sRGB bytes.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CompactYakuiVertex {
+    pos: yakui_core::geometry::Vec2,
+    texcoord: yakui_core::geometry::Vec2,
+    color: [u8; 4],
+}
+
+// SAFETY: the f32s are followed by exactly four bytes, so there is no padding
+unsafe impl PlainData for CompactYakuiVertex {}
+
+// SAFETY: made up of eight f32s filling its whole size, so there is no padding in whichever order they're laid out
+unsafe impl PlainData for yakui_core::paint::Vertex {}
+const _: () =
+    assert!(std::mem::size_of::<yakui_core::paint::Vertex>() == 8 * std::mem::size_of::<f32>());
+
+/// A yakui instance along with its renderer state and input handling, which is driven by a miniquad [`Context`] unless
+/// you're testing, see [`YakuiMiniQuad::set_window_space`].
+pub struct YakuiMiniQuad<B: GraphicsBackend + ?Sized = Context> {
     ui: Yakui,
-    state: YakuiMiniquadState,
+    state: YakuiMiniquadState<B>,
     has_keyboard_focus: bool,
     has_mouse_focus: bool,
+    modifiers: Modifiers,
+    touches: HashMap<u64, yakui_core::geometry::Vec2>,
+    primary_touch: Option<u64>,
+    touch_scrolling: bool,
+    two_finger_scroll: bool,
+    held_buttons: HashSet<YakuiMouseButton>,
+    held_keys: HashSet<YakuiKeyCode>,
+    /// The modifier keys held on either side of the keyboard, so releasing one doesn't clear a modifier the other still holds.
+    held_modifier_keys: HashSet<KeyCode>,
+    cursor_position: Option<yakui_core::geometry::Vec2>,
+    cursor_over_ui: bool,
+    viewport: Option<Rect>,
+    scale: f32,
+    /// The coordinate space of the window as of the last frame or resize, as miniquad can only be asked for it while
+    /// its window is open.
+    window: Option<CoordinateSpace>,
+    /// Whether `window` was given with [`YakuiMiniQuad::set_window_space`] rather than asked from miniquad.
+    window_space_fixed: bool,
+    /// Scratch space for walking the dom when hit testing, kept around to not allocate on every mouse motion.
+    hit_test_stack: Vec<WidgetId>,
+    /// The widget taking keyboard input the last sunk click landed on, as yakui doesn't expose its selection.
+    keyboard_widget: Option<WidgetId>,
+    captured_buttons: HashSet<YakuiMouseButton>,
+    consumed_events: Vec<ConsumedEvent>,
+    input_capture: InputCapture,
 }
 
-impl YakuiMiniQuad {
-    pub fn new(ctx: &mut Context) -> YakuiMiniQuad {
+impl<B: GraphicsBackend + ?Sized> YakuiMiniQuad<B> {
+    pub fn new(ctx: &mut B) -> Self {
+        Self::with_renderer(YakuiRenderer::new(ctx))
+    }
+
+    /// Creates an instance drawing through a renderer shared with other instances, see [`stack::YakuiStack`] for
+    /// routing input between them.
+    pub fn with_renderer(renderer: YakuiRenderer<B>) -> Self {
         YakuiMiniQuad {
-            state: YakuiMiniquadState::new(ctx),
+            state: YakuiMiniquadState::with_renderer(renderer),
             ui: Yakui::new(),
             has_keyboard_focus: false,
             has_mouse_focus: false,
+            modifiers: Modifiers::empty(),
+            touches: HashMap::new(),
+            primary_touch: None,
+            touch_scrolling: false,
+            two_finger_scroll: true,
+            held_buttons: HashSet::new(),
+            held_keys: HashSet::new(),
+            held_modifier_keys: HashSet::new(),
+            cursor_position: None,
+            cursor_over_ui: false,
+            viewport: None,
+            scale: 1.0,
+            window: None,
+            window_space_fixed: false,
+            hit_test_stack: Vec::new(),
+            keyboard_widget: None,
+            captured_buttons: HashSet::new(),
+            consumed_events: Vec::new(),
+            input_capture: InputCapture::default(),
         }
     }
 
     /// Returns true if the last mouse or keyboard event was sunk by yakui, and should not be handled by your game.
+    ///
+    /// This only reflects the last event handled, see [`YakuiMiniQuad::input_capture`] for a per-frame answer.
     pub fn has_input_focus(&self) -> bool {
         self.has_mouse_focus || self.has_keyboard_focus
     }
@@ -157,9 +266,87 @@ impl YakuiMiniQuad {
         self.has_keyboard_focus
     }
 
-    /// Returns true if the last mouse event was sunk by yakui, and should not be handled by your game.
+    /// Returns true if the cursor is over a yakui widget or the last mouse event was sunk by yakui, and the mouse should not be handled by your game.
     pub fn has_mouse_focus(&self) -> bool {
-        self.has_mouse_focus
+        self.has_mouse_focus || self.cursor_over_ui
+    }
+
+    /// Returns true if the cursor is over a yakui widget interested in the mouse, as of the last layout.
+    ///
+    /// This stays true after the cursor leaves the window without a button held, unless you call
+    /// [`YakuiMiniQuad::mouse_left_window`].
+    pub fn is_cursor_over_ui(&self) -> bool {
+        self.cursor_over_ui
+    }
+
+    /// Sets whether dragging two fingers scrolls the widget between them, which is enabled by default.
+    ///
+    /// When disabled, touches other than the first one are ignored.
+    pub fn set_two_finger_scroll(&mut self, enabled: bool) {
+        self.two_finger_scroll = enabled;
+    }
+
+    /// Releases every mouse button, key, modifier and touch yakui thinks is held, and moves the cursor out of the window.
+    ///
+    /// This is called when the window is minimized or loses focus, since the matching releases never reach us then.
+    pub fn reset_input(&mut self) {
+        // move the cursor away first, so releasing the buttons doesn't count as a click
+        self.move_cursor(None);
+
+        if self.primary_touch.take().is_some() {
+            self.held_buttons.insert(YakuiMouseButton::One);
+        }
+        self.touches.clear();
+        self.touch_scrolling = false;
+        self.captured_buttons.clear();
+        self.keyboard_widget = None;
+
+        for button in std::mem::take(&mut self.held_buttons) {
+            self.ui.handle_event(Event::MouseButtonChanged {
+                button,
+                down: false,
+            });
+        }
+
+        for key in std::mem::take(&mut self.held_keys) {
+            self.ui.handle_event(Event::KeyChanged { key, down: false });
+        }
+
+        self.sync_modifiers(Modifiers::empty());
+
+        self.has_mouse_focus = false;
+        self.has_keyboard_focus = false;
+    }
+
+    /// Confines the ui to the given region of the window, in physical pixels with a top-left origin, or lets it cover
+    /// the whole window again if there is none.
+    ///
+    /// Input outside of the viewport is ignored, and the ui is scissored to it when drawn.
+    pub fn set_viewport(&mut self, viewport: Option<Rect>) {
+        self.viewport = viewport;
+        self.update_viewport();
+    }
+
+    /// Scales the ui by the given factor on top of the dpi scale of the window, which is 1.0 by default.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.update_viewport();
+    }
+
+    /// Sets the coordinate space of the window instead of asking miniquad for it every frame, or asks miniquad again if
+    /// there is none.
+    ///
+    /// This lets the ui be driven without a miniquad window, like in tests. [`YakuiMiniQuad::resize_event`] still
+    /// resizes the given space.
+    pub fn set_window_space(&mut self, space: Option<CoordinateSpace>) {
+        self.window_space_fixed = space.is_some();
+        self.window = space;
+        self.update_viewport();
+    }
+
+    /// Returns the region of the window the ui is confined to, if it's been set.
+    pub fn viewport(&self) -> Option<Rect> {
+        self.viewport
     }
 
     /// Returns a reference to the internal Yakui context.
@@ -167,15 +354,46 @@ impl YakuiMiniQuad {
         &mut self.ui
     }
 
+    /// Makes a miniquad texture you own available to yakui widgets, see [`YakuiMiniquadState::add_user_texture`].
+    pub fn add_user_texture(&mut self, texture: B::Texture) -> yakui_core::TextureId {
+        self.state.add_user_texture(texture)
+    }
+
+    /// Stops making a texture available to yakui, see [`YakuiMiniquadState::remove_user_texture`].
+    pub fn remove_user_texture(&mut self, id: yakui_core::TextureId) -> Option<B::Texture> {
+        self.state.remove_user_texture(id)
+    }
+
+    /// Returns a reference to the internal renderer state, for registering custom pipelines and the like.
+    pub fn state(&mut self) -> &mut YakuiMiniquadState<B> {
+        &mut self.state
+    }
+
     /// Updates the viewport size and calls start on the internal yakui context, binding it to the current thread.
     pub fn start(&mut self) {
-        self.update();
+        self.update_window_space();
         self.ui.start();
     }
 
     /// Calls finish on the internal yakui context, preparing the context for rendering.
-    pub fn finish(&mut self) {
+    ///
+    /// Returns which input yakui wants as of this frame, along with the events it sunk since the previous one.
+    pub fn finish(&mut self) -> &InputCapture {
         self.ui.finish();
+        self.update_cursor_over_ui();
+
+        self.input_capture = InputCapture {
+            wants_pointer: self.cursor_over_ui || !self.captured_buttons.is_empty(),
+            wants_keyboard: self.has_keyboard_selection(),
+            consumed_events: std::mem::take(&mut self.consumed_events),
+        };
+
+        &self.input_capture
+    }
+
+    /// Returns the input capture taken by the last call to [`YakuiMiniQuad::finish`].
+    pub fn input_capture(&self) -> &InputCapture {
+        &self.input_capture
     }
 
     /// Wraps calling start and finish, where start will now be called before your closure is invoked and finish will be invoked after.
@@ -183,217 +401,1122 @@ impl YakuiMiniQuad {
     where
         F: FnOnce(&mut Yakui),
     {
-        self.update();
+        self.update_window_space();
 
         self.ui.start();
         ui_update_function(&mut self.ui);
-        self.ui.finish();
+        self.finish();
+    }
+
+    /// Renders the queued ui draw commands.
+    pub fn draw(&mut self, ctx: &mut B) {
+        self.state.paint(ctx, &mut self.ui);
+    }
+
+    /// Renders the queued ui draw commands into a texture of the given size instead, cleared to transparent first.
+    ///
+    /// Call this outside of any render pass, the texture is reused between calls, see [`YakuiMiniquadState::paint_to_texture`].
+    pub fn draw_to_texture(&mut self, ctx: &mut B, width: u32, height: u32) -> B::Texture {
+        self.state
+            .paint_to_texture(ctx, &mut self.ui, width, height, Some((0.0, 0.0, 0.0, 0.0)))
+    }
+
+    /// Deletes the texture created by [`YakuiMiniQuad::draw_to_texture`], if there is one.
+    pub fn drop_render_target(&mut self, ctx: &mut B) {
+        self.state.drop_render_target(ctx);
+    }
+
+    /// Tells yakui about the modifiers miniquad reports as held, if they differ from what it was last told.
+    ///
+    /// Every keyboard event carries the full modifier state, so this also repairs modifiers whose key presses or releases
+    /// were missed, e.g. while the window was unfocused.
+    fn sync_modifiers(&mut self, modifiers: Modifiers) {
+        // modifier keys whose release was missed aren't held anymore either
+        self.held_modifier_keys.retain(|&key| {
+            miniquad_key_to_modifier(key).is_some_and(|modifier| modifiers.contains(modifier))
+        });
+
+        if modifiers != self.modifiers {
+            self.modifiers = modifiers;
+            self.ui.handle_event(Event::ModifiersChanged(modifiers));
+        }
+    }
+
+    /// Records the event as consumed if yakui sunk it, passing through whether it did.
+    fn record_consumed(&mut self, sunk: bool, event: ConsumedEvent) -> bool {
+        if sunk {
+            self.consumed_events.push(event);
+        }
+        sunk
+    }
+
+    /// Returns whether a widget taking keyboard input is focused, forgetting it once it's gone.
+    fn has_keyboard_selection(&mut self) -> bool {
+        let layout = self.ui.layout_dom();
+        self.keyboard_widget = self.keyboard_widget.filter(|&id| {
+            layout.get(id).is_some_and(|node| {
+                node.event_interest
+                    .contains(EventInterest::FOCUSED_KEYBOARD)
+            })
+        });
+        self.keyboard_widget.is_some()
+    }
+
+    /// Keeps track of the widget taking keyboard input yakui focuses after a press of the primary button.
+    ///
+    /// yakui clears its selection when nobody sinks the press, and widgets like text boxes focus themselves when they
+    /// sink it. Presses sunk by other widgets leave the selection alone.
+    fn track_keyboard_widget(&mut self, sunk: bool) {
+        if !sunk {
+            self.keyboard_widget = None;
+            return;
+        }
+
+        let Some(position) = self.cursor_position else {
+            return;
+        };
+        let focused = widget_at(&self.ui, &mut self.hit_test_stack, position, |interest| {
+            interest.intersects(EventInterest::MOUSE_ALL)
+                && interest.contains(EventInterest::FOCUSED_KEYBOARD)
+        });
+        if focused.is_some() {
+            self.keyboard_widget = focused;
+        }
+    }
+
+    /// Returns whether the position is inside the viewport, which it always is before the window size is known.
+    fn is_inside_viewport(&self, position: yakui_core::geometry::Vec2) -> bool {
+        let viewport = match (self.viewport, self.window) {
+            (Some(viewport), _) => viewport,
+            (None, Some(window)) => self.viewport_or(window.physical_size),
+            (None, None) => return true,
+        };
+        position.cmpge(viewport.pos()).all() && position.cmplt(viewport.max()).all()
+    }
+
+    fn move_cursor(&mut self, position: Option<yakui_core::geometry::Vec2>) {
+        self.cursor_position = position;
+        self.ui.handle_event(Event::CursorMoved(position));
+        self.update_cursor_over_ui();
+    }
+
+    fn update_cursor_over_ui(&mut self) {
+        self.cursor_over_ui = self
+            .cursor_position
+            .is_some_and(|position| self.is_mouse_widget_at(position));
+    }
+
+    /// Returns whether the given position is over a widget that wants mouse events, as of the last layout.
+    fn is_mouse_widget_at(&mut self, position: yakui_core::geometry::Vec2) -> bool {
+        widget_at(&self.ui, &mut self.hit_test_stack, position, |interest| {
+            interest.intersects(EventInterest::MOUSE_ALL)
+        })
+        .is_some()
+    }
+
+    /// Releases the mouse button held down by the primary touch, moving the cursor away first when the touch was
+    /// cancelled so that the release doesn't count as a click. Returns whether yakui sunk the release.
+    fn release_primary_touch(&mut self, position: Option<yakui_core::geometry::Vec2>) -> bool {
+        if self.primary_touch.take().is_some() {
+            self.move_cursor(position);
+            self.captured_buttons.remove(&YakuiMouseButton::One);
+            self.has_mouse_focus = self.ui.handle_event(Event::MouseButtonChanged {
+                button: YakuiMouseButton::One,
+                down: false,
+            });
+            self.has_mouse_focus
+        } else {
+            false
+        }
+    }
+
+    fn touch_centroid(&self) -> yakui_core::geometry::Vec2 {
+        let sum = self
+            .touches
+            .values()
+            .fold(yakui_core::geometry::Vec2::ZERO, |sum, position| {
+                sum + *position
+            });
+        sum / self.touches.len().max(1) as f32
+    }
+}
+
+// The input half of `miniquad::EventHandler`, call these from the matching methods of your own handler. YakuiMiniQuad
+// can't draw without a `Context`, so it deliberately doesn't implement the trait itself.
+impl<B: GraphicsBackend + ?Sized> YakuiMiniQuad<B> {
+    /// Asks miniquad for the coordinate space of the window, unless it was given with [`YakuiMiniQuad::set_window_space`],
+    /// and updates the viewport to match.
+    fn update_window_space(&mut self) {
+        if !self.window_space_fixed {
+            self.window = Some(CoordinateSpace::of_window());
+        }
+        self.update_viewport();
+    }
+
+    /// Updates the scale factor, surface size and viewport of the internal yakui context to match the window, once
+    /// its size is known.
+    fn update_viewport(&mut self) {
+        let Some(mut space) = self.window else {
+            return;
+        };
+        space.scale_factor *= self.scale;
+        let viewport = self.viewport_or(space.physical_size);
+        space.apply_to_viewport(&mut self.ui, viewport);
+    }
+
+    /// Returns the configured viewport, or one covering a window of the given size if there is none.
+    fn viewport_or(&self, window_size: yakui_core::geometry::Vec2) -> Rect {
+        self.viewport
+            .unwrap_or(Rect::from_pos_size(Default::default(), window_size))
+    }
+
+    /// Tells yakui the window was resized.
+    pub fn resize_event(&mut self, width: f32, height: f32) {
+        let size = yakui_core::geometry::Vec2::new(width, height);
+        match &mut self.window {
+            Some(window) => window.physical_size = size,
+            None => self.window = Some(CoordinateSpace::new(size, 1.0)),
+        }
+
+        let viewport = self.viewport_or(size);
+        self.ui.handle_event(Event::ViewportChanged(viewport));
+    }
+
+    /// Tells yakui the cursor moved, or left the viewport if the position is outside of it.
+    ///
+    /// Motion outside of the window is only reported while a button is held, so call
+    /// [`YakuiMiniQuad::mouse_left_window`] when your platform tells you the cursor left it, or widgets stay hovered.
+    pub fn mouse_motion_event(&mut self, x: f32, y: f32) {
+        // miniquad has no event for the cursor leaving the window, but keeps reporting motion outside of it while
+        // a button is held
+        let mouse_position = yakui_core::geometry::Vec2::new(x, y);
+        if !self.is_inside_viewport(mouse_position) {
+            if self.cursor_position.is_some() {
+                self.move_cursor(None);
+            }
+            return;
+        }
+
+        self.move_cursor(Some(mouse_position));
+    }
+
+    /// Tells yakui the cursor left the window, clearing hover state, as miniquad has no event for it.
+    pub fn mouse_left_window(&mut self) {
+        if self.cursor_position.is_some() {
+            self.move_cursor(None);
+        }
+    }
+
+    /// Tells yakui the mouse wheel was scrolled, returning whether yakui sunk it.
+    pub fn mouse_wheel_event(&mut self, x: f32, y: f32) -> bool {
+        let sunk = self.ui.handle_event(Event::MouseScroll {
+            delta: yakui_core::geometry::Vec2 { x, y },
+        });
+        self.has_mouse_focus = self.record_consumed(sunk, ConsumedEvent::MouseWheel { x, y });
+        sunk
+    }
+
+    /// Tells yakui a mouse button was pressed, returning whether yakui sunk it, or false for buttons yakui doesn't know.
+    pub fn mouse_button_down_event(&mut self, button: MouseButton, _x: f32, _y: f32) -> bool {
+        let Some(mouse_button) = miniquad_mouse_button_to_yakui(button) else {
+            return false;
+        };
+
+        self.held_buttons.insert(mouse_button);
+        let sunk = self.ui.handle_event(Event::MouseButtonChanged {
+            button: mouse_button,
+            down: true,
+        });
+        if sunk {
+            self.captured_buttons.insert(mouse_button);
+        }
+        if mouse_button == YakuiMouseButton::One {
+            self.track_keyboard_widget(sunk);
+        }
+        self.has_mouse_focus =
+            self.record_consumed(sunk, ConsumedEvent::MouseButton { button, down: true });
+        sunk
+    }
+
+    /// Tells yakui a mouse button was released, returning whether yakui sunk it, or false for buttons yakui doesn't know.
+    pub fn mouse_button_up_event(&mut self, button: MouseButton, _x: f32, _y: f32) -> bool {
+        let Some(mouse_button) = miniquad_mouse_button_to_yakui(button) else {
+            return false;
+        };
+
+        self.held_buttons.remove(&mouse_button);
+        self.captured_buttons.remove(&mouse_button);
+        let sunk = self.ui.handle_event(Event::MouseButtonChanged {
+            button: mouse_button,
+            down: false,
+        });
+        self.has_mouse_focus = self.record_consumed(
+            sunk,
+            ConsumedEvent::MouseButton {
+                button,
+                down: false,
+            },
+        );
+        sunk
+    }
+
+    /// Tells yakui a character was typed, returning whether yakui sunk it, or false for characters yakui never sees.
+    pub fn char_event(&mut self, character: char, keymods: KeyMods, _repeat: bool) -> bool {
+        self.sync_modifiers(miniquad_keymods_to_yakui(keymods));
+
+        match character {
+            '\u{E000}'..='\u{F8FF}' => {
+                // Skip unicode private use area, which miniquad seems to emit
+                // for non-character button presses. A bug in miniquad?
+                false
+            }
+            _ => {
+                let sunk = self.ui.handle_event(Event::TextInput(character));
+                self.has_keyboard_focus =
+                    self.record_consumed(sunk, ConsumedEvent::Char(character));
+                sunk
+            }
+        }
+    }
+
+    /// Tells yakui a key was pressed, returning whether yakui sunk it, or false for keys yakui doesn't know.
+    pub fn key_down_event(&mut self, keycode: KeyCode, keymods: KeyMods, _repeat: bool) -> bool {
+        // some platforms report the modifiers from before the key changed, so account for modifier keys themselves
+        let mut modifiers = miniquad_keymods_to_yakui(keymods);
+        if let Some(modifier) = miniquad_key_to_modifier(keycode) {
+            self.held_modifier_keys.insert(keycode);
+            modifiers.insert(modifier);
+        }
+        self.sync_modifiers(modifiers);
+
+        let Some(key_code) = miniquad_key_to_yakui(keycode) else {
+            return false;
+        };
+        self.held_keys.insert(key_code);
+        let sunk = self.ui.handle_event(Event::KeyChanged {
+            key: key_code,
+            down: true,
+        });
+        self.has_keyboard_focus = self.record_consumed(
+            sunk,
+            ConsumedEvent::Key {
+                keycode,
+                down: true,
+            },
+        );
+        sunk
+    }
+
+    /// Tells yakui a key was released, returning whether yakui sunk it, or false for keys yakui doesn't know.
+    pub fn key_up_event(&mut self, keycode: KeyCode, keymods: KeyMods) -> bool {
+        let mut modifiers = miniquad_keymods_to_yakui(keymods);
+        if let Some(modifier) = miniquad_key_to_modifier(keycode) {
+            self.held_modifier_keys.remove(&keycode);
+            // the key on the other side of the keyboard may still hold the same modifier
+            let still_held = self
+                .held_modifier_keys
+                .iter()
+                .any(|&key| miniquad_key_to_modifier(key) == Some(modifier));
+            modifiers.set(modifier, still_held);
+        }
+        self.sync_modifiers(modifiers);
+
+        let Some(key_code) = miniquad_key_to_yakui(keycode) else {
+            return false;
+        };
+        self.held_keys.remove(&key_code);
+        let sunk = self.ui.handle_event(Event::KeyChanged {
+            key: key_code,
+            down: false,
+        });
+        self.has_keyboard_focus = self.record_consumed(
+            sunk,
+            ConsumedEvent::Key {
+                keycode,
+                down: false,
+            },
+        );
+        sunk
+    }
+
+    /// Tells yakui about a touch, where the first finger acts as the mouse and a second one scrolls.
+    ///
+    /// Returns whether yakui sunk the press, scroll or release the touch turned into, or false if it turned into none.
+    pub fn touch_event(&mut self, phase: TouchPhase, id: u64, x: f32, y: f32) -> bool {
+        let position = yakui_core::geometry::Vec2::new(x, y);
+
+        match phase {
+            TouchPhase::Started => {
+                if !self.is_inside_viewport(position) {
+                    return false;
+                }
+
+                self.touches.insert(id, position);
+
+                if self.touches.len() == 1 {
+                    // the first finger down acts as the mouse
+                    self.primary_touch = Some(id);
+                    self.move_cursor(Some(position));
+                    let sunk = self.ui.handle_event(Event::MouseButtonChanged {
+                        button: YakuiMouseButton::One,
+                        down: true,
+                    });
+                    if sunk {
+                        self.captured_buttons.insert(YakuiMouseButton::One);
+                    }
+                    self.track_keyboard_widget(sunk);
+                    self.has_mouse_focus =
+                        self.record_consumed(sunk, ConsumedEvent::Touch { id, phase });
+                    sunk
+                } else {
+                    if self.touches.len() == 2 && self.two_finger_scroll {
+                        // a second finger turns the press into a scroll gesture instead
+                        self.release_primary_touch(None);
+                        self.touch_scrolling = true;
+                        self.move_cursor(Some(self.touch_centroid()));
+                    }
+                    false
+                }
+            }
+            TouchPhase::Moved => {
+                let previous_centroid = self.touch_centroid();
+                match self.touches.get_mut(&id) {
+                    Some(touch) => *touch = position,
+                    None => return false,
+                }
+
+                if self.touch_scrolling {
+                    let centroid = self.touch_centroid();
+                    self.move_cursor(Some(centroid));
+                    // touches are in physical pixels, but yakui scrolls by logical ones
+                    let scale_factor = self.ui.layout_dom().scale_factor();
+                    let sunk = self.ui.handle_event(Event::MouseScroll {
+                        delta: (previous_centroid - centroid) / scale_factor,
+                    });
+                    self.has_mouse_focus =
+                        self.record_consumed(sunk, ConsumedEvent::Touch { id, phase });
+                    sunk
+                } else {
+                    if self.primary_touch == Some(id) {
+                        self.move_cursor(Some(position));
+                    }
+                    false
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                if self.touches.remove(&id).is_none() {
+                    return false;
+                }
+
+                let mut sunk = false;
+                if self.primary_touch == Some(id) {
+                    let release_position = match phase {
+                        TouchPhase::Ended => Some(position),
+                        _ => None,
+                    };
+                    sunk = self.release_primary_touch(release_position);
+                    self.record_consumed(sunk, ConsumedEvent::Touch { id, phase });
+                }
+
+                if self.touches.len() < 2 {
+                    self.touch_scrolling = false;
+                }
+
+                if self.touches.is_empty() {
+                    // nothing is hovering a touch screen once every finger is lifted
+                    self.move_cursor(None);
+                }
+
+                sunk
+            }
+        }
+    }
+
+    /// Releases all held input, since miniquad reports both minimizing and losing focus this way.
+    pub fn window_minimized_event(&mut self) {
+        self.reset_input();
+    }
+
+    /// Releases all held input, in case any releases were missed while the window was away.
+    pub fn window_restored_event(&mut self) {
+        self.reset_input();
+    }
+
+    /// Releases all held input.
+    pub fn quit_requested_event(&mut self) {
+        self.reset_input();
+    }
+}
+
+/// The vertex attributes of the geometry yakui is rendered with, which custom pipelines need to be created with too.
+pub const VERTEX_ATTRIBUTES: [VertexAttribute; 3] = [
+    VertexAttribute::new("in_pos", VertexFormat::Float2),
+    VertexAttribute::new("in_texcoord", VertexFormat::Float2),
+    VertexAttribute::new("in_color", VertexFormat::Float4),
+];
+
+/// The vertex attributes of the geometry yakui is rendered with in [`VertexLayout::Compact`].
+///
+/// The color is made up of sRGB bytes, which miniquad passes to the shader as floats from 0 to 255 rather than normalized.
+pub const COMPACT_VERTEX_ATTRIBUTES: [VertexAttribute; 3] = [
+    VertexAttribute::new("in_pos", VertexFormat::Float2),
+    VertexAttribute::new("in_texcoord", VertexFormat::Float2),
+    VertexAttribute::new("in_color", VertexFormat::Byte4),
+];
+
+/// How the vertices of the geometry yakui is rendered with are laid out, see [`YakuiRenderer::with_vertex_layout`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VertexLayout {
+    /// A float position, texcoord and linear color, 32 bytes per vertex, see [`VERTEX_ATTRIBUTES`].
+    #[default]
+    Standard,
+    /// A float position and texcoord, and a color in sRGB bytes, 20 bytes per vertex, see [`COMPACT_VERTEX_ATTRIBUTES`].
+    ///
+    /// This cuts the geometry uploaded each frame by more than a third, which helps on mobile and the web, at the cost
+    /// of converting colors to sRGB on the CPU.
+    Compact,
+}
+
+impl VertexLayout {
+    /// Returns the vertex attributes of this layout, which custom pipelines need to be created with.
+    pub fn attributes(self) -> &'static [VertexAttribute] {
+        match self {
+            VertexLayout::Standard => &VERTEX_ATTRIBUTES,
+            VertexLayout::Compact => &COMPACT_VERTEX_ATTRIBUTES,
+        }
+    }
+
+    /// Returns the size of a single vertex in bytes.
+    pub fn vertex_size(self) -> usize {
+        match self {
+            VertexLayout::Standard => size_of::<YakuiVertex>(),
+            VertexLayout::Compact => size_of::<CompactYakuiVertex>(),
+        }
+    }
+}
+
+/// What to do with draw calls using a yakui pipeline which there's no miniquad pipeline for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownPipelineBehaviour {
+    /// Silently skips the draw call.
+    Skip,
+    /// Draws it with the main pipeline instead.
+    DrawWithMain,
+    /// Skips the draw call, logging an error the first time each unknown pipeline is encountered.
+    #[default]
+    Error,
+}
+
+impl UnknownPipelineBehaviour {
+    /// Returns whether draw calls using the given pipeline are drawn with the main pipeline, logging an error the
+    /// first time each pipeline is skipped if asked to, which keeps track of them in `reported`.
+    fn draws(
+        self,
+        pipeline: yakui_core::paint::Pipeline,
+        reported: &mut HashSet<yakui_core::paint::Pipeline>,
+    ) -> bool {
+        match self {
+            UnknownPipelineBehaviour::Skip => false,
+            UnknownPipelineBehaviour::DrawWithMain => true,
+            UnknownPipelineBehaviour::Error => {
+                if reported.insert(pipeline) {
+                    log::error!(
+                        "[yakui-miniquad]: no pipeline registered for {:?}, skipping draw calls using it",
+                        pipeline
+                    );
+                }
+                false
+            }
+        }
+    }
+}
+
+/// The color space the built-in pipelines write colors to the render target in.
+///
+/// Either way, colors are blended with textures in linear space, as yakui hands out linear vertex colors and textures
+/// are decoded from sRGB with the exact transfer function. Blending with what's already in the render target is done
+/// by the GPU in whatever space the render target stores colors in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputColorSpace {
+    /// Encodes colors to sRGB, for render targets which store sRGB colors but don't convert them themselves,
+    /// like the default framebuffer of miniquad.
+    #[default]
+    Srgb,
+    /// Writes linear colors, for sRGB framebuffers which convert them themselves, or linear render targets which
+    /// your own pipeline converts later on.
+    Linear,
+}
+
+impl OutputColorSpace {
+    /// Returns the uniforms of the built-in pipelines which make them output in this color space.
+    fn uniforms(self) -> [u8; 4] {
+        let output_srgb: f32 = match self {
+            OutputColorSpace::Srgb => 1.0,
+            OutputColorSpace::Linear => 0.0,
+        };
+        output_srgb.to_ne_bytes()
+    }
+}
+
+/// How a texture is sampled when yakui draws it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureSampling {
+    pub min_filter: FilterMode,
+    pub mag_filter: FilterMode,
+    pub wrap: TextureWrap,
+    /// How to sample between mipmaps, which are only generated when this isn't [`MipmapFilterMode::None`].
+    ///
+    /// Note that WebGL 1 and GLES 2 only support mipmaps for textures with power of two sizes.
+    pub mipmap_filter: MipmapFilterMode,
+}
+
+impl TextureSampling {
+    /// Sampling which picks the nearest texel, for crisp pixel art.
+    pub const NEAREST: TextureSampling = TextureSampling {
+        min_filter: FilterMode::Nearest,
+        mag_filter: FilterMode::Nearest,
+        wrap: TextureWrap::Clamp,
+        mipmap_filter: MipmapFilterMode::None,
+    };
+
+    /// Sampling which blends between texels.
+    pub const LINEAR: TextureSampling = TextureSampling {
+        min_filter: FilterMode::Linear,
+        mag_filter: FilterMode::Linear,
+        wrap: TextureWrap::Clamp,
+        mipmap_filter: MipmapFilterMode::None,
+    };
+
+    /// Returns the sampling a yakui texture asks for with its filters and address mode.
+    pub fn of_texture(texture: &yakui_core::paint::Texture) -> TextureSampling {
+        let filter = |filter| match filter {
+            yakui_core::paint::TextureFilter::Linear => FilterMode::Linear,
+            yakui_core::paint::TextureFilter::Nearest => FilterMode::Nearest,
+        };
+        let wrap = match texture.address_mode {
+            yakui_core::paint::AddressMode::ClampToEdge => TextureWrap::Clamp,
+            yakui_core::paint::AddressMode::Repeat => TextureWrap::Repeat,
+        };
+
+        TextureSampling {
+            min_filter: filter(texture.min_filter),
+            mag_filter: filter(texture.mag_filter),
+            wrap,
+            mipmap_filter: MipmapFilterMode::None,
+        }
+    }
+
+    fn uses_mipmaps(&self) -> bool {
+        self.mipmap_filter != MipmapFilterMode::None
+    }
+
+    fn apply<B: GraphicsBackend + ?Sized>(&self, ctx: &mut B, texture: B::Texture) {
+        ctx.texture_set_min_filter(texture, self.min_filter, self.mipmap_filter);
+        ctx.texture_set_mag_filter(texture, self.mag_filter);
+        ctx.texture_set_wrap(texture, self.wrap, self.wrap);
+        if self.uses_mipmaps() {
+            ctx.texture_generate_mipmaps(texture);
+        }
+    }
+}
+
+impl Default for TextureSampling {
+    fn default() -> Self {
+        TextureSampling::LINEAR
+    }
+}
+
+/// A user-provided pipeline which draw calls using a given yakui pipeline are rendered with.
+struct CustomPipeline<Pipeline> {
+    pipeline: Pipeline,
+    uniforms: Vec<u8>,
+}
+
+/// A texture known to the renderer, along with who is responsible for deleting it.
+enum TextureEntry<Texture> {
+    /// Created from a texture managed by yakui, deleted when yakui removes it or in [`YakuiMiniquadState::drop_textures`].
+    Managed {
+        texture: Texture,
+        /// The sampling the yakui texture asks for, used unless overridden.
+        requested_sampling: TextureSampling,
+    },
+    /// Registered with [`YakuiMiniquadState::add_user_texture`], never deleted by the renderer.
+    User(Texture),
+}
+
+impl<Texture: Copy> TextureEntry<Texture> {
+    fn texture(&self) -> Texture {
+        match self {
+            TextureEntry::Managed { texture, .. } | TextureEntry::User(texture) => *texture,
+        }
+    }
+}
+
+/// Renders the paint output of yakui with a [`GraphicsBackend`], which is a miniquad [`Context`] unless you're testing.
+pub struct YakuiMiniquadState<B: GraphicsBackend + ?Sized = Context> {
+    renderer: YakuiRenderer<B>,
+    textures: HashMap<yakui_core::TextureId, TextureEntry<B::Texture>>,
+    next_user_texture: u64,
+
+    output_color_space: OutputColorSpace,
+    texture_sampling: Option<TextureSampling>,
+    texture_sampling_overrides: HashMap<yakui_core::TextureId, TextureSampling>,
+    render_target: Option<RenderTarget<B::Texture, B::RenderPass>>,
+    buffer_policy: BufferPolicy,
+    /// The sets of buffers frames rotate between, one per frame in flight.
+    buffer_sets: Vec<Vec<BatchBuffers<B::Buffer>>>,
+    current_buffer_set: usize,
+    /// A hash of everything the uploaded geometry and draw commands were made from, to skip uploading identical frames.
+    geometry_hash: Option<u64>,
+    /// The geometry of the batches, kept across frames so its memory is reused.
+    batch_data: Vec<BatchData>,
+    /// The indices of a call too big for a single batch, unwrapped into the full range of its vertices.
+    unwrapped_indices: Vec<u32>,
+    /// The pieces a call too big for a single batch is split into, as ranges of its unwrapped indices and the
+    /// vertices they refer to.
+    oversized_pieces: Vec<(Range<usize>, RangeInclusive<u32>)>,
+    commands: Vec<DrawCommand<B::Texture>>,
+}
+
+/// An offscreen texture the UI can be rendered into, along with the render pass drawing into it.
+struct RenderTarget<Texture, Pass> {
+    texture: Texture,
+    pass: Pass,
+    width: u32,
+    height: u32,
+}
+
+/// The number of vertices a single batch can address with 16-bit indices.
+const MAX_VERTICES_PER_BATCH: usize = u16::MAX as usize + 1;
+
+/// Geometry for a part of the frame small enough to be addressed with 16-bit indices.
+#[derive(Default)]
+struct BatchData {
+    /// The vertices in [`VertexLayout::Standard`], or in [`VertexLayout::Compact`] in `compact_vertices`.
+    vertices: Vec<YakuiVertex>,
+    compact_vertices: Vec<CompactYakuiVertex>,
+    indices: Vec<u16>,
+}
+
+impl BatchData {
+    fn vertex_count(&self) -> usize {
+        self.vertices.len() + self.compact_vertices.len()
+    }
+
+    fn vertex_bytes(&self) -> &[u8] {
+        if self.compact_vertices.is_empty() {
+            as_bytes(&self.vertices)
+        } else {
+            as_bytes(&self.compact_vertices)
+        }
+    }
+}
+
+/// The GPU buffers a [`BatchData`] gets uploaded into.
+struct BatchBuffers<Buffer> {
+    vertices: StreamBuffer<Buffer>,
+    indices: StreamBuffer<Buffer>,
+}
+
+impl<Buffer: Copy> BatchBuffers<Buffer> {
+    fn new(vertex_layout: VertexLayout) -> Self {
+        BatchBuffers {
+            vertices: StreamBuffer::new(BufferType::VertexBuffer, vertex_layout.vertex_size()),
+            indices: StreamBuffer::new(BufferType::IndexBuffer, size_of::<u16>()),
+        }
+    }
+}
+
+struct DrawCommand<Texture> {
+    batch: usize,
+    index_range: Range<u32>,
+    texture: Texture,
+    pipeline: yakui_core::paint::Pipeline,
+    clip: Option<Rect>,
+}
+
+impl<Texture: PartialEq> DrawCommand<Texture> {
+    /// Returns true if the indices of `next` directly follow these ones and are drawn with the same state.
+    fn can_merge(&self, next: &DrawCommand<Texture>) -> bool {
+        self.batch == next.batch
+            && self.index_range.end == next.index_range.start
+            && self.texture == next.texture
+            && self.pipeline == next.pipeline
+            && self.clip == next.clip
+    }
+}
+
+/// A hasher in the manner of FxHash, mixing in a word at a time, which is fast enough to run over all of the geometry
+/// every frame.
+///
+/// Skipping the upload on a matching hash is probabilistic: it isn't cryptographic, so two different frames can hash
+/// the same, and the second would then be drawn with the geometry of the first until the ui changes again. With 64 bits
+/// that's unlikely enough to be worth not comparing all of the geometry against the previous frame every frame.
+#[derive(Default)]
+struct GeometryHasher(u64);
+
+impl GeometryHasher {
+    fn add_word(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+impl Hasher for GeometryHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            self.add_word(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+
+        let rest = words.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add_word(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, word: u64) {
+        self.add_word(word);
+    }
+
+    fn write_usize(&mut self, word: usize) {
+        self.add_word(word as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<B: GraphicsBackend + ?Sized> YakuiMiniquadState<B> {
+    pub fn new(ctx: &mut B) -> Self {
+        Self::with_renderer(YakuiRenderer::new(ctx))
+    }
+
+    /// Creates a state drawing through a renderer shared with other states, so pipelines are only created once.
+    pub fn with_renderer(renderer: YakuiRenderer<B>) -> Self {
+        YakuiMiniquadState {
+            renderer,
+            textures: HashMap::new(),
+            next_user_texture: 0,
+            output_color_space: OutputColorSpace::default(),
+            texture_sampling: None,
+            texture_sampling_overrides: HashMap::new(),
+            render_target: None,
+            buffer_policy: BufferPolicy::default(),
+            buffer_sets: Vec::new(),
+            current_buffer_set: 0,
+            geometry_hash: None,
+            batch_data: Vec::new(),
+            unwrapped_indices: Vec::new(),
+            oversized_pieces: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Returns the renderer this state draws through, which can be cloned to share it with another state.
+    pub fn renderer(&self) -> &YakuiRenderer<B> {
+        &self.renderer
+    }
+
+    /// Renders draw calls using the given yakui pipeline with your own miniquad pipeline, see [`YakuiRenderer::register_pipeline`].
+    ///
+    /// This affects every state sharing the renderer.
+    pub fn register_pipeline(
+        &mut self,
+        yakui_pipeline: yakui_core::paint::Pipeline,
+        pipeline: B::Pipeline,
+        uniforms: &[u8],
+    ) {
+        self.renderer
+            .register_pipeline(yakui_pipeline, pipeline, uniforms);
     }
 
-    /// Renders the queued ui draw commands.
-    pub fn draw(&mut self, ctx: &mut Context) {
-        self.state.paint(ctx, &mut self.ui);
+    /// Updates the uniforms of a pipeline registered with [`YakuiMiniquadState::register_pipeline`], returns false if there is none.
+    pub fn set_pipeline_uniforms(
+        &mut self,
+        yakui_pipeline: yakui_core::paint::Pipeline,
+        uniforms: &[u8],
+    ) -> bool {
+        self.renderer
+            .set_pipeline_uniforms(yakui_pipeline, uniforms)
     }
-}
-
-impl EventHandler for YakuiMiniQuad {
-    fn update(&mut self) {
-        let (screen_w, screen_h) = screen_size();
 
-        self.ui.set_scale_factor(dpi_scale());
-        self.ui.set_surface_size(yakui_core::geometry::Vec2 {
-            x: screen_w,
-            y: screen_h,
-        });
-        self.ui
-            .set_unscaled_viewport(yakui_core::geometry::Rect::from_pos_size(
-                Default::default(),
-                [screen_w, screen_h].into(),
-            ));
+    /// Removes a pipeline registered with [`YakuiMiniquadState::register_pipeline`], returning it so it can be deleted.
+    pub fn unregister_pipeline(
+        &mut self,
+        yakui_pipeline: yakui_core::paint::Pipeline,
+    ) -> Option<B::Pipeline> {
+        self.renderer.unregister_pipeline(yakui_pipeline)
     }
 
-    fn draw(&mut self) {
-        panic!("[yakui-miniquad]: YakuiMiniQuad cannot draw as an event handler, please wrap it with a custom event handler that calls `YakuiMiniQuad::draw(&mut self, &mut Context)`")
+    /// Sets what happens to draw calls using a yakui pipeline there's neither a built-in nor a registered pipeline for.
+    ///
+    /// This affects every state sharing the renderer.
+    pub fn set_unknown_pipeline_behaviour(&mut self, behaviour: UnknownPipelineBehaviour) {
+        self.renderer.set_unknown_pipeline_behaviour(behaviour);
     }
 
-    fn resize_event(&mut self, width: f32, height: f32) {
-        let viewport_position = yakui_core::geometry::Vec2 { x: 0.0, y: 0.0 };
-        let viewport_size = yakui_core::geometry::Vec2 {
-            x: width,
-            y: height,
-        };
-        self.ui
-            .handle_event(Event::ViewportChanged(Rect::from_pos_size(
-                viewport_position,
-                viewport_size,
-            )));
+    /// Returns the color space the built-in pipelines output colors in.
+    pub fn output_color_space(&self) -> OutputColorSpace {
+        self.output_color_space
     }
 
-    fn mouse_motion_event(&mut self, x: f32, y: f32) {
-        let mouse_position = yakui_core::geometry::Vec2::new(x, y);
-        self.ui
-            .handle_event(Event::CursorMoved(Some(mouse_position)));
+    /// Sets the color space the built-in pipelines output colors in, to match the render target yakui is drawn into.
+    ///
+    /// Pipelines registered with [`YakuiMiniquadState::register_pipeline`] handle color spaces themselves.
+    pub fn set_output_color_space(&mut self, color_space: OutputColorSpace) {
+        self.output_color_space = color_space;
     }
 
-    fn mouse_wheel_event(&mut self, x: f32, y: f32) {
-        self.has_mouse_focus = self.ui.handle_event(Event::MouseScroll {
-            delta: yakui_core::geometry::Vec2 { x, y },
-        });
+    /// Returns how textures created for yakui are sampled unless overridden for their id, or `None` if they're sampled
+    /// the way their yakui texture asks for.
+    pub fn texture_sampling(&self) -> Option<TextureSampling> {
+        self.texture_sampling
     }
 
-    fn mouse_button_down_event(&mut self, button: MouseButton, _x: f32, _y: f32) {
-        if let Some(mouse_button) = miniquad_mouse_button_to_yakui(button) {
-            self.has_mouse_focus = self.ui.handle_event(Event::MouseButtonChanged {
-                button: mouse_button,
-                down: true,
-            });
+    /// Sets how textures created for yakui are sampled unless overridden for their id, including the ones already created.
+    ///
+    /// With `None`, which is the default, they're sampled with the filters and address mode of their yakui texture.
+    /// Textures added with [`YakuiMiniquadState::add_user_texture`] keep the sampling you created them with.
+    pub fn set_texture_sampling(&mut self, ctx: &mut B, sampling: Option<TextureSampling>) {
+        self.texture_sampling = sampling;
+
+        for (id, entry) in &self.textures {
+            if let TextureEntry::Managed {
+                texture,
+                requested_sampling,
+            } = entry
+            {
+                if !self.texture_sampling_overrides.contains_key(id) {
+                    self.sampling_for(*id, *requested_sampling)
+                        .apply(ctx, *texture);
+                }
+            }
         }
     }
 
-    fn mouse_button_up_event(&mut self, button: MouseButton, _x: f32, _y: f32) {
-        if let Some(mouse_button) = miniquad_mouse_button_to_yakui(button) {
-            self.has_mouse_focus = self.ui.handle_event(Event::MouseButtonChanged {
-                button: mouse_button,
-                down: false,
-            });
+    /// Overrides how the texture behind the given id is sampled, or stops overriding it if `sampling` is `None`.
+    ///
+    /// This works for textures added with [`YakuiMiniquadState::add_user_texture`] too, though they keep the last sampling
+    /// applied to them once no longer overridden, and ones swapped in with [`YakuiMiniquadState::set_user_texture`]
+    /// keep their own.
+    pub fn set_texture_sampling_override(
+        &mut self,
+        ctx: &mut B,
+        id: yakui_core::TextureId,
+        sampling: Option<TextureSampling>,
+    ) {
+        match sampling {
+            Some(sampling) => {
+                self.texture_sampling_overrides.insert(id, sampling);
+            }
+            None => {
+                self.texture_sampling_overrides.remove(&id);
+            }
         }
-    }
 
-    fn char_event(&mut self, character: char, _keymods: KeyMods, _repeat: bool) {
-        match character {
-            '\u{E000}'..='\u{F8FF}' => {
-                // Skip unicode private use area, which miniquad seems to emit
-                // for non-character button presses. A bug in miniquad?
+        match self.textures.get(&id) {
+            Some(TextureEntry::Managed {
+                texture,
+                requested_sampling,
+            }) => self
+                .sampling_for(id, *requested_sampling)
+                .apply(ctx, *texture),
+            Some(TextureEntry::User(texture)) => {
+                if let Some(sampling) = sampling {
+                    sampling.apply(ctx, *texture);
+                }
             }
-            _ => self.has_keyboard_focus = self.ui.handle_event(Event::TextInput(character)),
+            None => {}
         }
     }
 
-    fn key_down_event(&mut self, keycode: KeyCode, _keymods: KeyMods, _repeat: bool) {
-        if let Some(key_code) = miniquad_key_to_yakui(keycode) {
-            self.has_keyboard_focus = self.ui.handle_event(Event::KeyChanged {
-                key: key_code,
-                down: true,
-            });
+    /// Returns how the texture behind the given id is sampled, given the sampling its yakui texture asks for.
+    fn sampling_for(
+        &self,
+        id: yakui_core::TextureId,
+        requested_sampling: TextureSampling,
+    ) -> TextureSampling {
+        self.texture_sampling_overrides
+            .get(&id)
+            .copied()
+            .or(self.texture_sampling)
+            .unwrap_or(requested_sampling)
+    }
+
+    /// Makes a texture you created yourself available to yakui widgets through the returned id, like in an image widget.
+    ///
+    /// The texture stays yours, it's never deleted by the renderer, not even by [`YakuiMiniquadState::drop_textures`].
+    pub fn add_user_texture(&mut self, texture: B::Texture) -> yakui_core::TextureId {
+        let id = yakui_core::TextureId::User(self.next_user_texture);
+        self.next_user_texture += 1;
+        self.textures.insert(id, TextureEntry::User(texture));
+        id
+    }
+
+    /// Replaces the texture behind an id returned by [`YakuiMiniquadState::add_user_texture`], returning the previous one.
+    pub fn set_user_texture(
+        &mut self,
+        id: yakui_core::TextureId,
+        texture: B::Texture,
+    ) -> Option<B::Texture> {
+        match self.textures.get_mut(&id) {
+            Some(entry @ TextureEntry::User(_)) => {
+                let previous = entry.texture();
+                *entry = TextureEntry::User(texture);
+                Some(previous)
+            }
+            _ => None,
         }
     }
 
-    fn key_up_event(&mut self, keycode: KeyCode, _keymods: KeyMods) {
-        if let Some(key_code) = miniquad_key_to_yakui(keycode) {
-            self.has_keyboard_focus = self.ui.handle_event(Event::KeyChanged {
-                key: key_code,
-                down: false,
-            });
+    /// Stops making a texture added with [`YakuiMiniquadState::add_user_texture`] available to yakui, returning it so you can delete it.
+    pub fn remove_user_texture(&mut self, id: yakui_core::TextureId) -> Option<B::Texture> {
+        match self.textures.get(&id) {
+            Some(TextureEntry::User(texture)) => {
+                let texture = *texture;
+                self.textures.remove(&id);
+                self.texture_sampling_overrides.remove(&id);
+                Some(texture)
+            }
+            _ => None,
         }
     }
-}
 
-pub struct YakuiMiniquadState {
-    main_pipeline: Pipeline,
-    text_pipeline: Pipeline,
-    textures: HashMap<yakui_core::TextureId, TextureId>,
-    layout: Bindings,
+    /// Deletes every texture created for yakui, textures added with [`YakuiMiniquadState::add_user_texture`] are left alone.
+    pub fn drop_textures(&mut self, ctx: &mut B) {
+        self.textures.retain(|_, entry| match entry {
+            TextureEntry::Managed { texture, .. } => {
+                ctx.delete_texture(*texture);
+                false
+            }
+            TextureEntry::User(_) => true,
+        });
+    }
 
-    default_texture: TextureId,
-    vertices: BufferId,
-    indices: BufferId,
-    commands: Vec<DrawCommand>,
-}
+    /// Returns how the buffers the UI is uploaded into are sized and reused.
+    pub fn buffer_policy(&self) -> BufferPolicy {
+        self.buffer_policy
+    }
 
-struct DrawCommand {
-    index_range: Range<u32>,
-    texture: TextureId,
-    pipeline: yakui_core::paint::Pipeline,
-    clip: Option<Rect>,
-}
+    /// Sets how the buffers the UI is uploaded into are sized and reused, deleting the buffers of frames no longer in flight.
+    pub fn set_buffer_policy(&mut self, ctx: &mut B, policy: BufferPolicy) {
+        let sets = policy.frames_in_flight.max(1);
+        while self.buffer_sets.len() > sets {
+            for mut buffers in self.buffer_sets.pop().unwrap() {
+                buffers.vertices.delete(ctx);
+                buffers.indices.delete(ctx);
+            }
+        }
+        self.current_buffer_set %= sets;
+        self.buffer_policy = policy;
+        self.geometry_hash = None;
+    }
 
-impl YakuiMiniquadState {
-    pub fn new(ctx: &mut Context) -> Self {
-        let main_pipeline = make_main_pipeline(
-            ctx,
-            &[BufferLayout::default()],
-            &[
-                VertexAttribute::new("in_pos", VertexFormat::Float2),
-                VertexAttribute::new("in_texcoord", VertexFormat::Float2),
-                VertexAttribute::new("in_color", VertexFormat::Float4),
-            ],
-        );
+    /// Deletes the vertex and index buffers the UI was uploaded into, they're created again the next time it's painted.
+    pub fn drop_buffers(&mut self, ctx: &mut B) {
+        for mut buffers in self.buffer_sets.drain(..).flatten() {
+            buffers.vertices.delete(ctx);
+            buffers.indices.delete(ctx);
+        }
+        self.current_buffer_set = 0;
+        self.geometry_hash = None;
+    }
 
-        let text_pipeline = make_text_pipeline(
-            ctx,
-            &[BufferLayout::default()],
-            &[
-                VertexAttribute::new("in_pos", VertexFormat::Float2),
-                VertexAttribute::new("in_texcoord", VertexFormat::Float2),
-                VertexAttribute::new("in_color", VertexFormat::Float4),
-            ],
-        );
+    /// Renders the UI into the current render pass, which is expected to cover the surface size yakui was given.
+    pub fn paint(&mut self, ctx: &mut B, state: &mut yakui_core::Yakui) {
+        self.paint_with_target_size(ctx, state, None);
+    }
 
-        let textures = HashMap::new();
-
-        let vertex_buffers = vec![ctx.new_buffer(
-            BufferType::VertexBuffer,
-            BufferUsage::Stream,
-            BufferSource::empty::<yakui_core::paint::Vertex>(1),
-        )];
-        let index_buffer = ctx.new_buffer(
-            BufferType::IndexBuffer,
-            BufferUsage::Stream,
-            BufferSource::empty::<u16>(1),
-        );
+    /// Renders geometry yakui has already painted into the current render pass, for when you call [`Yakui::paint`]
+    /// yourself, for example to inspect the paint DOM first.
+    ///
+    /// `viewport` is the one yakui laid the ui out in, see [`LayoutDom::unscaled_viewport`](yakui_core::layout::LayoutDom::unscaled_viewport).
+    pub fn paint_dom(&mut self, ctx: &mut B, paint: &PaintDom, viewport: Rect) {
+        self.paint_dom_with_target_size(ctx, paint, viewport, None);
+    }
 
-        let layout = Bindings {
-            vertex_buffers,
-            index_buffer,
-            images: Vec::new(),
+    /// Renders the UI into an offscreen texture of the given size, which is created on first use and recreated whenever the size changes.
+    ///
+    /// If the size differs from the surface size yakui was given, the UI is scaled to fill the texture. The returned texture
+    /// stays owned by the renderer until [`YakuiMiniquadState::drop_render_target`] is called. Like any miniquad render
+    /// target on OpenGL its rows are stored bottom first, so flip the texture coordinates when drawing it.
+    pub fn paint_to_texture(
+        &mut self,
+        ctx: &mut B,
+        state: &mut yakui_core::Yakui,
+        width: u32,
+        height: u32,
+        clear_color: Option<(f32, f32, f32, f32)>,
+    ) -> B::Texture {
+        let target = match self.render_target.take() {
+            Some(target) if target.width == width && target.height == height => target,
+            previous => {
+                if let Some(previous) = previous {
+                    ctx.delete_render_pass(previous.pass);
+                    ctx.delete_texture(previous.texture);
+                }
+                make_render_target(ctx, width, height)
+            }
         };
 
-        let default_texture = ctx.new_texture(
-            TextureAccess::Static,
-            TextureSource::Bytes(&[255, 255, 255, 255]),
-            TextureParams {
-                kind: TextureKind::Texture2D,
-                format: TextureFormat::RGBA8,
-                wrap: TextureWrap::Clamp,
-                min_filter: FilterMode::Linear,
-                mag_filter: FilterMode::Linear,
-                width: 1,
-                height: 1,
-                mipmap_filter: MipmapFilterMode::None,
-                allocate_mipmaps: false,
-                sample_count: 0,
+        let action = match clear_color {
+            Some(color) => PassAction::Clear {
+                color: Some(color),
+                depth: None,
+                stencil: None,
             },
+            None => PassAction::Nothing,
+        };
+
+        ctx.begin_pass(Some(target.pass), action);
+        ctx.apply_viewport(0, 0, width as i32, height as i32);
+        self.paint_with_target_size(
+            ctx,
+            state,
+            Some(yakui_core::geometry::Vec2::new(width as f32, height as f32)),
         );
+        ctx.end_render_pass();
 
-        YakuiMiniquadState {
-            main_pipeline,
-            text_pipeline,
-            textures,
-            layout,
-            default_texture,
-            vertices: ctx.new_buffer(
-                BufferType::VertexBuffer,
-                BufferUsage::Stream,
-                BufferSource::empty::<yakui_core::paint::Vertex>(1),
-            ),
-            indices: ctx.new_buffer(
-                BufferType::IndexBuffer,
-                BufferUsage::Stream,
-                BufferSource::empty::<u16>(1),
-            ),
-            commands: Vec::new(),
-        }
+        let texture = target.texture;
+        self.render_target = Some(target);
+        texture
     }
 
-    pub fn drop_textures(&mut self, ctx: &mut Context) {
-        for (_, texture) in &self.textures {
-            ctx.delete_texture(*texture);
+    /// Returns the texture last rendered into by [`YakuiMiniquadState::paint_to_texture`], if there is one.
+    pub fn render_target(&self) -> Option<B::Texture> {
+        self.render_target.as_ref().map(|target| target.texture)
+    }
+
+    /// Deletes the texture and render pass created by [`YakuiMiniquadState::paint_to_texture`].
+    pub fn drop_render_target(&mut self, ctx: &mut B) {
+        if let Some(target) = self.render_target.take() {
+            ctx.delete_render_pass(target.pass);
+            ctx.delete_texture(target.texture);
         }
     }
 
-    pub fn paint(&mut self, ctx: &mut Context, state: &mut yakui_core::Yakui) {
+    fn paint_with_target_size(
+        &mut self,
+        ctx: &mut B,
+        state: &mut yakui_core::Yakui,
+        target_size: Option<yakui_core::geometry::Vec2>,
+    ) {
+        // the ui is confined to yakui's viewport, which it offsets geometry by but not the clip rects
+        let viewport = state.layout_dom().unscaled_viewport();
         let paint = state.paint();
+        self.paint_dom_with_target_size(ctx, paint, viewport, target_size);
+    }
 
+    fn paint_dom_with_target_size(
+        &mut self,
+        ctx: &mut B,
+        paint: &PaintDom,
+        viewport: Rect,
+        target_size: Option<yakui_core::geometry::Vec2>,
+    ) {
         self.update_textures(ctx, paint);
 
         let layers = paint.layers();
@@ -401,172 +1524,425 @@ impl YakuiMiniquadState {
             return;
         }
 
-        self.update_buffers(ctx, paint);
+        // idle uis paint the same geometry frame after frame, which is still in the buffers, a hash collision between
+        // two different frames is accepted as the rare cost of not comparing all of it, see `GeometryHasher`
+        let geometry_hash = self.hash_geometry(paint);
+        if self.geometry_hash != Some(geometry_hash) || !self.reuse_buffers() {
+            self.update_buffers(ctx, paint);
+            self.geometry_hash = Some(geometry_hash);
+        }
+
+        let color_space_uniforms = self.output_color_space.uniforms();
+        let target = target_size.unwrap_or(paint.surface_size()).as_uvec2();
+
+        let mut renderer = self.renderer.resources.borrow_mut();
+        let renderer = &mut *renderer;
+
+        // the state applied by the previous draw call, so it's only applied again once it changes
+        let mut last_pipeline = None;
+        let mut last_bindings = None;
+        let mut last_clip = None;
+        let mut last_scissor = None;
+        let mut clip_visible = false;
+
+        for command in &self.commands {
+            let (pipeline, uniforms) = match renderer.custom_pipelines.get(&command.pipeline) {
+                Some(custom_pipeline) => (
+                    custom_pipeline.pipeline,
+                    custom_pipeline.uniforms.as_slice(),
+                ),
+                None => match renderer.builtin_pipelines.get(&command.pipeline) {
+                    Some(builtin_pipeline) => (*builtin_pipeline, color_space_uniforms.as_slice()),
+                    None => {
+                        if !renderer
+                            .unknown_pipeline_behaviour
+                            .draws(command.pipeline, &mut renderer.reported_unknown_pipelines)
+                        {
+                            continue;
+                        }
+                        let main_pipeline =
+                            renderer.builtin_pipelines[&yakui_core::paint::Pipeline::Main];
+                        (main_pipeline, color_space_uniforms.as_slice())
+                    }
+                },
+            };
 
-        {
-            let mut last_clip = None;
+            if last_clip != Some(command.clip) {
+                last_clip = Some(command.clip);
 
-            for command in &self.commands {
-                match command.pipeline {
-                    yakui_core::paint::Pipeline::Main => ctx.apply_pipeline(&self.main_pipeline),
-                    yakui_core::paint::Pipeline::Text => ctx.apply_pipeline(&self.text_pipeline),
-                    _ => continue,
-                }
+                let clip = match command.clip {
+                    Some(rect) => Rect::from_pos_size(rect.pos() + viewport.pos(), rect.size())
+                        .constrain(viewport),
+                    None => viewport,
+                };
 
-                if command.clip != last_clip {
-                    last_clip = command.clip;
-
-                    let surface = paint.surface_size().as_uvec2();
-
-                    match command.clip {
-                        Some(rect) => {
-                            let pos = rect.pos().as_uvec2();
-                            let size = rect.size().as_uvec2();
-
-                            let max = (pos + size).min(surface);
-                            let size = yakui_core::geometry::UVec2::new(
-                                max.x.saturating_sub(pos.x),
-                                max.y.saturating_sub(pos.y),
-                            );
-
-                            // If the scissor rect isn't valid, we can skip this
-                            // entire draw call.
-                            if pos.x > surface.x || pos.y > surface.y || size.x == 0 || size.y == 0
-                            {
-                                continue;
-                            }
-
-                            ctx.apply_scissor_rect(
-                                pos.x as i32,
-                                pos.y as i32,
-                                size.x as i32,
-                                size.y as i32,
-                            );
-                        }
-                        None => {
-                            ctx.apply_scissor_rect(0, 0, surface.x as i32, surface.y as i32);
+                match clip_to_scissor(clip, paint.surface_size(), target) {
+                    Some(scissor) => {
+                        clip_visible = true;
+                        if last_scissor != Some(scissor) {
+                            let (x, y, w, h) = scissor;
+                            ctx.apply_scissor_rect(x, y, w, h);
+                            last_scissor = Some(scissor);
                         }
                     }
+                    None => clip_visible = false,
+                }
+            }
+
+            // nothing inside the clip rect would be visible, so the whole draw call can be skipped
+            if !clip_visible {
+                continue;
+            }
+
+            if last_pipeline != Some((pipeline, uniforms)) {
+                ctx.apply_pipeline(&pipeline);
+                if !uniforms.is_empty() {
+                    ctx.apply_uniforms(uniforms);
                 }
+                last_pipeline = Some((pipeline, uniforms));
+                // bindings are tied to the pipeline they're applied with
+                last_bindings = None;
+            }
 
-                let base_element = command.index_range.start as i32;
-                let number_of_elements_to_draw =
-                    (command.index_range.end - command.index_range.start) as i32;
-                let command_bindings = Bindings {
-                    vertex_buffers: vec![self.vertices],
-                    index_buffer: self.indices,
-                    images: vec![command.texture],
+            if last_bindings != Some((command.batch, command.texture)) {
+                let buffers = &self.buffer_sets[self.current_buffer_set][command.batch];
+                let (Some(vertices), Some(indices)) =
+                    (buffers.vertices.buffer(), buffers.indices.buffer())
+                else {
+                    continue;
                 };
-
-                ctx.apply_bindings(&command_bindings);
-                ctx.draw(base_element, number_of_elements_to_draw, 1);
+                ctx.apply_bindings(&[vertices], indices, &[command.texture]);
+                last_bindings = Some((command.batch, command.texture));
             }
+
+            let base_element = command.index_range.start as i32;
+            let number_of_elements_to_draw =
+                (command.index_range.end - command.index_range.start) as i32;
+            ctx.draw(base_element, number_of_elements_to_draw, 1);
         }
     }
 
     fn update_buffers_with_layer(
         &mut self,
-        ctx: &mut Context,
         layer: &yakui_core::paint::PaintLayer,
+        batches: &mut Vec<BatchData>,
+        used_batches: &mut usize,
     ) {
         let commands = &layer.calls;
-
-        let mut draw_vertices: Vec<YakuiVertex> = Vec::new();
-        let mut draw_indices: Vec<u16> = Vec::new();
-        let mut draw_commands = Vec::new();
+        let default_texture = self.renderer.default_texture();
 
         for mesh in commands {
-            let vertices = mesh.vertices.iter().map(|v| YakuiVertex {
-                pos: v.position,
-                texcoord: v.texcoord,
-                color: v.color,
-            });
+            let texture = mesh
+                .texture
+                .and_then(|index| self.textures.get(&index))
+                .map(TextureEntry::texture)
+                .unwrap_or(default_texture);
+
+            let draw_state = (texture, mesh.pipeline, mesh.clip);
+            if mesh.vertices.len() <= MAX_VERTICES_PER_BATCH {
+                self.add_geometry(
+                    batches,
+                    used_batches,
+                    &mesh.vertices,
+                    mesh.indices.iter().copied(),
+                    draw_state,
+                );
+            } else {
+                self.add_oversized_geometry(batches, used_batches, mesh, draw_state);
+            }
+        }
+    }
+
+    /// Adds a call which yakui merged from more meshes than a batch can address, splitting it into pieces which each fit in one.
+    ///
+    /// yakui offsets the indices of every mesh merged into a call by the vertices before it, wrapping around at 16 bits,
+    /// so they're unwrapped first, by picking the vertex closest to the highest one referred to so far. This holds as
+    /// long as the meshes themselves have fewer than 32768 vertices.
+    fn add_oversized_geometry(
+        &mut self,
+        batches: &mut Vec<BatchData>,
+        used_batches: &mut usize,
+        mesh: &yakui_core::paint::PaintCall,
+        draw_state: (B::Texture, yakui_core::paint::Pipeline, Option<Rect>),
+    ) {
+        let mut unwrapped = std::mem::take(&mut self.unwrapped_indices);
+        unwrapped.clear();
+
+        let mut highest = 0u32;
+        for &index in &mesh.indices {
+            let lowest = highest.saturating_sub(u16::MAX as u32 / 2);
+            let index = lowest + ((index as u32).wrapping_sub(lowest) & u16::MAX as u32);
+            highest = highest.max(index);
+            unwrapped.push(index);
+        }
+
+        // every piece is made of consecutive triangles whose vertices a single batch can address
+        let mut pieces = std::mem::take(&mut self.oversized_pieces);
+        pieces.clear();
+        for (triangle_index, triangle) in unwrapped.chunks_exact(3).enumerate() {
+            let element = triangle_index * 3;
+            let lowest = triangle.iter().min().copied().unwrap();
+            let highest = triangle.iter().max().copied().unwrap();
+
+            match pieces.last_mut() {
+                Some((elements, vertices))
+                    if lowest >= *vertices.start()
+                        && ((highest - vertices.start()) as usize) < MAX_VERTICES_PER_BATCH =>
+                {
+                    elements.end = element + 3;
+                    *vertices = *vertices.start()..=highest.max(*vertices.end());
+                }
+                _ => pieces.push((element..element + 3, lowest..=highest)),
+            }
+        }
+
+        for (elements, vertices) in pieces.drain(..) {
+            let first_vertex = *vertices.start();
+            self.add_geometry(
+                batches,
+                used_batches,
+                &mesh.vertices[first_vertex as usize..=*vertices.end() as usize],
+                unwrapped[elements]
+                    .iter()
+                    .map(|&index| (index - first_vertex) as u16),
+                draw_state,
+            );
+        }
+
+        self.unwrapped_indices = unwrapped;
+        self.oversized_pieces = pieces;
+    }
+
+    /// Adds vertices along with indices relative to them to the current batch, or a new one if they don't fit anymore,
+    /// and a command drawing them.
+    fn add_geometry(
+        &mut self,
+        batches: &mut Vec<BatchData>,
+        used_batches: &mut usize,
+        vertices: &[yakui_core::paint::Vertex],
+        indices: impl ExactSizeIterator<Item = u16>,
+        (texture, pipeline, clip): (B::Texture, yakui_core::paint::Pipeline, Option<Rect>),
+    ) {
+        // start a new batch whenever the current one can't take all of the vertices
+        let needs_new_batch = match batches[..*used_batches].last() {
+            Some(batch) => batch.vertex_count() + vertices.len() > MAX_VERTICES_PER_BATCH,
+            None => true,
+        };
+
+        if needs_new_batch {
+            if *used_batches == batches.len() {
+                batches.push(BatchData::default());
+            }
+            *used_batches += 1;
+        }
 
-            let base = draw_vertices.len() as u16;
-            let indices: Vec<u16> = mesh.indices.iter().map(|&index| base + index).collect();
+        let batch_index = *used_batches - 1;
+        let batch = &mut batches[batch_index];
+
+        // indices are relative to the vertices, so offset them by everything uploaded
+        // before them in the same batch, including the meshes of any previous layers
+        let base = batch.vertex_count() as u16;
+        let start = batch.indices.len() as u32;
+        let end = start + indices.len() as u32;
+        batch.indices.extend(indices.map(|index| base + index));
+
+        match self.renderer.vertex_layout() {
+            VertexLayout::Standard => {
+                batch.vertices.extend(vertices.iter().map(|v| YakuiVertex {
+                    pos: v.position,
+                    texcoord: v.texcoord,
+                    color: v.color,
+                }));
+            }
+            VertexLayout::Compact => {
+                batch.compact_vertices.extend(vertices.iter().map(|v| {
+                    let color = Color::from_linear(v.color);
+                    CompactYakuiVertex {
+                        pos: v.position,
+                        texcoord: v.texcoord,
+                        color: [color.r, color.g, color.b, color.a],
+                    }
+                }));
+            }
+        }
 
-            let start = draw_indices.len() as u32;
-            let end = start + indices.len() as u32;
+        let new_draw_command = DrawCommand {
+            batch: batch_index,
+            index_range: start..end,
+            texture,
+            pipeline,
+            clip,
+        };
+
+        // merge consecutive meshes drawn the same way into a single draw call
+        match self.commands.last_mut() {
+            Some(last) if last.can_merge(&new_draw_command) => {
+                last.index_range.end = new_draw_command.index_range.end;
+            }
+            _ => self.commands.push(new_draw_command),
+        }
+    }
 
-            let texture = mesh.texture.and_then(|index| self.textures.get(&index));
+    /// Uploads the batches into the next set of buffers, letting the buffers of batches which aren't needed anymore age.
+    fn upload_batches(&mut self, ctx: &mut B, batches: &[BatchData]) {
+        let sets = self.buffer_policy.frames_in_flight.max(1);
+        if self.buffer_sets.len() < sets {
+            self.buffer_sets.resize_with(sets, Vec::new);
+        }
+        self.current_buffer_set = (self.current_buffer_set + 1) % sets;
 
-            draw_vertices.extend(vertices);
-            draw_indices.extend(&indices);
+        let set = &mut self.buffer_sets[self.current_buffer_set];
+        while set.len() < batches.len() {
+            set.push(BatchBuffers::new(self.renderer.vertex_layout()));
+        }
 
-            let new_draw_command = DrawCommand {
-                index_range: start..end,
-                texture: *texture.unwrap_or(&self.default_texture),
-                pipeline: mesh.pipeline,
-                clip: mesh.clip,
+        for (batch_index, buffers) in set.iter_mut().enumerate() {
+            let (vertices, indices) = match batches.get(batch_index) {
+                Some(batch) => (batch.vertex_bytes(), as_bytes(&batch.indices)),
+                None => (&[][..], &[][..]),
             };
+            buffers.vertices.upload(ctx, vertices, &self.buffer_policy);
+            buffers.indices.upload(ctx, indices, &self.buffer_policy);
+        }
+    }
+
+    /// Draws from the geometry uploaded last frame again, returns false if it needs to be uploaded again as buffers are due to shrink.
+    fn reuse_buffers(&mut self) -> bool {
+        let Some(set) = self.buffer_sets.get_mut(self.current_buffer_set) else {
+            return false;
+        };
 
-            draw_commands.push(new_draw_command);
+        let policy = &self.buffer_policy;
+        if set.iter().any(|buffers| {
+            buffers.vertices.wants_shrink(policy) || buffers.indices.wants_shrink(policy)
+        }) {
+            return false;
         }
 
-        // upload the buffers at last
-        let size_of_vertex_data_in_bytes =
-            draw_vertices.len() * size_of::<yakui_core::paint::Vertex>();
-        if ctx.buffer_size(self.vertices) < size_of_vertex_data_in_bytes {
-            ctx.delete_buffer(self.vertices);
-            self.vertices = ctx.new_buffer(
-                BufferType::VertexBuffer,
-                BufferUsage::Stream,
-                BufferSource::empty::<yakui_core::paint::Vertex>(size_of_vertex_data_in_bytes),
-            );
-            ctx.buffer_update(self.vertices, BufferSource::slice(&draw_vertices));
-            self.layout.vertex_buffers = vec![self.vertices];
-        } else {
-            ctx.buffer_update(self.vertices, BufferSource::slice(&draw_vertices));
+        for buffers in set {
+            buffers.vertices.reuse();
+            buffers.indices.reuse();
         }
+        true
+    }
 
-        let size_of_index_data_in_bytes = draw_indices.len() * size_of::<u16>();
+    /// Hashes everything [`YakuiMiniquadState::update_buffers`] makes the buffers and draw commands from.
+    fn hash_geometry(&self, paint: &PaintDom) -> u64 {
+        let mut hasher = GeometryHasher::default();
 
-        if ctx.buffer_size(self.indices) < size_of_index_data_in_bytes {
-            ctx.delete_buffer(self.indices);
-            self.indices = ctx.new_buffer(
-                BufferType::IndexBuffer,
-                BufferUsage::Stream,
-                BufferSource::empty::<u16>(size_of_index_data_in_bytes),
-            );
-            ctx.buffer_update(self.indices, BufferSource::slice(&draw_indices));
-            self.layout.index_buffer = self.indices;
-        } else {
-            ctx.buffer_update(self.indices, BufferSource::slice(&draw_indices));
+        hasher.write_usize(paint.layers().len());
+        for layer in paint.layers().iter() {
+            hasher.write_usize(layer.calls.len());
+            for call in &layer.calls {
+                hasher.write_usize(call.vertices.len());
+                hasher.write(as_bytes(&call.vertices));
+                hasher.write_usize(call.indices.len());
+                hasher.write(as_bytes(&call.indices));
+
+                // the texture the id currently stands for, as user textures can be swapped out
+                call.texture
+                    .and_then(|id| self.textures.get(&id))
+                    .map(TextureEntry::texture)
+                    .hash(&mut hasher);
+                call.pipeline.hash(&mut hasher);
+                call.clip
+                    .map(|clip| [clip.pos(), clip.size()].map(|v| v.to_array().map(f32::to_bits)))
+                    .hash(&mut hasher);
+            }
         }
 
-        self.commands.extend(draw_commands);
+        hasher.finish()
     }
 
-    fn update_buffers(&mut self, ctx: &mut Context, paint: &PaintDom) {
+    /// Collects the geometry of every layer into batches of vertex and index buffers, where each
+    /// batch holds as many draw calls as can be addressed with 16-bit indices.
+    fn update_buffers(&mut self, ctx: &mut B, paint: &PaintDom) {
         self.commands.clear();
 
+        // reuse the geometry of the previous frame, so a frame only allocates when the ui grows
+        let mut batches = std::mem::take(&mut self.batch_data);
+        for batch in &mut batches {
+            batch.vertices.clear();
+            batch.compact_vertices.clear();
+            batch.indices.clear();
+        }
+        let mut used_batches = 0;
+
         for layer in paint.layers().iter() {
-            self.update_buffers_with_layer(ctx, layer);
+            self.update_buffers_with_layer(layer, &mut batches, &mut used_batches);
         }
+
+        // upload the buffers at last, once all the layers have been collected
+        self.upload_batches(ctx, &batches[..used_batches]);
+
+        self.batch_data = batches;
     }
 
-    fn update_textures(&mut self, ctx: &mut Context, paint: &PaintDom) {
+    fn update_textures(&mut self, ctx: &mut B, paint: &PaintDom) {
         for (id, texture) in paint.textures() {
-            self.textures
-                .entry(id.into())
-                .or_insert_with(|| make_texture(ctx, texture));
+            if !self.textures.contains_key(&id.into()) {
+                let entry = self.make_managed_texture(ctx, id.into(), texture);
+                self.textures.insert(id.into(), entry);
+            }
         }
 
         for (id, change) in paint.texture_edits() {
             match change {
                 yakui_core::paint::TextureChange::Added => {
-                    let texture = paint.texture(id).unwrap();
-                    self.textures.insert(id.into(), make_texture(ctx, texture));
+                    // usually already created above, as added textures are part of the paint dom too
+                    if !self.textures.contains_key(&id.into()) {
+                        let entry =
+                            self.make_managed_texture(ctx, id.into(), paint.texture(id).unwrap());
+                        self.textures.insert(id.into(), entry);
+                    }
                 }
                 yakui_core::paint::TextureChange::Removed => {
-                    if let Some(t) = self.textures.remove(&id.into()) {
-                        ctx.delete_texture(t);
+                    // only yakui managed textures can be removed through the paint dom, never user textures
+                    if let Some(TextureEntry::Managed { texture, .. }) =
+                        self.textures.remove(&id.into())
+                    {
+                        ctx.delete_texture(texture);
                     }
                 }
                 yakui_core::paint::TextureChange::Modified => {
-                    if let Some(existing) = self.textures.get_mut(&id.into()) {
-                        let texture = paint.texture(id).unwrap();
-                        ctx.texture_update(*existing, texture.data());
+                    let Some(TextureEntry::Managed {
+                        texture: existing,
+                        requested_sampling,
+                    }) = self.textures.get_mut(&id.into())
+                    else {
+                        continue;
+                    };
+                    let existing = *existing;
+                    let texture = paint.texture(id).unwrap();
+
+                    // the filters and address mode of the yakui texture may have changed too
+                    let requested = TextureSampling::of_texture(texture);
+                    let sampling_changed =
+                        std::mem::replace(requested_sampling, requested) != requested;
+                    let sampling = self.sampling_for(id.into(), requested);
+                    if sampling_changed {
+                        sampling.apply(ctx, existing);
+                    }
+
+                    // textures which couldn't be uploaded keep showing their placeholder
+                    let Some((format, data)) = resolve_texture_data(texture) else {
+                        continue;
+                    };
+
+                    // placeholders and textures which changed their format or size can't be updated in place
+                    let params = ctx.texture_params(existing);
+                    if params.format != format
+                        || UVec2::new(params.width, params.height) != texture.size()
+                    {
+                        ctx.delete_texture(existing);
+                        let entry = self.make_managed_texture(ctx, id.into(), texture);
+                        self.textures.insert(id.into(), entry);
+                        continue;
+                    }
+
+                    ctx.texture_update(existing, data);
+                    if sampling.uses_mipmaps() {
+                        ctx.texture_generate_mipmaps(existing);
                     }
                 }
             }
@@ -583,6 +1959,69 @@ fn miniquad_mouse_button_to_yakui(button: MouseButton) -> Option<yakui_core::inp
     }
 }
 
+/// Returns a widget under the given cursor position whose event interest matches, mirroring the hit testing yakui does
+/// internally but doesn't expose. `stack` is scratch space, which is left empty.
+fn widget_at(
+    ui: &Yakui,
+    stack: &mut Vec<WidgetId>,
+    position: yakui_core::geometry::Vec2,
+    matches: impl Fn(EventInterest) -> bool,
+) -> Option<WidgetId> {
+    let dom = ui.dom();
+    let layout = ui.layout_dom();
+    let position = (position - layout.unscaled_viewport().pos()) / layout.scale_factor();
+
+    stack.clear();
+    stack.push(dom.root());
+    while let Some(id) = stack.pop() {
+        let Some(node) = dom.get(id) else {
+            continue;
+        };
+        stack.extend_from_slice(&node.children);
+
+        let Some(layout_node) = layout.get(id) else {
+            continue;
+        };
+
+        if !matches(layout_node.event_interest) {
+            continue;
+        }
+
+        let mut rect = layout_node.rect;
+        let mut clipped_by = layout_node.clipped_by;
+        while let Some(clip) = clipped_by.and_then(|id| layout.get(id)) {
+            rect = rect.constrain(clip.rect);
+            clipped_by = clip.clipped_by;
+        }
+
+        if rect.contains_point(position) {
+            stack.clear();
+            return Some(id);
+        }
+    }
+
+    None
+}
+
+fn miniquad_keymods_to_yakui(keymods: KeyMods) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, keymods.shift);
+    modifiers.set(Modifiers::CONTROL, keymods.ctrl);
+    modifiers.set(Modifiers::ALT, keymods.alt);
+    modifiers.set(Modifiers::META, keymods.logo);
+    modifiers
+}
+
+fn miniquad_key_to_modifier(key: KeyCode) -> Option<Modifiers> {
+    match key {
+        KeyCode::LeftShift | KeyCode::RightShift => Some(Modifiers::SHIFT),
+        KeyCode::LeftControl | KeyCode::RightControl => Some(Modifiers::CONTROL),
+        KeyCode::LeftAlt | KeyCode::RightAlt => Some(Modifiers::ALT),
+        KeyCode::LeftSuper | KeyCode::RightSuper => Some(Modifiers::META),
+        _ => None,
+    }
+}
+
 fn miniquad_key_to_yakui(key: KeyCode) -> Option<YakuiKeyCode> {
     match key {
         KeyCode::Space => Some(YakuiKeyCode::Space),
@@ -710,37 +2149,133 @@ fn miniquad_key_to_yakui(key: KeyCode) -> Option<YakuiKeyCode> {
     }
 }
 
-fn resolve_texture_format(format: yakui_core::paint::TextureFormat) -> TextureFormat {
-    match format {
+/// Returns the pixels of a yakui texture as they are, along with the miniquad format they can be uploaded in.
+/// Returns `None` for formats miniquad has no equivalent of, or data which doesn't fit the size, for which the caller
+/// uploads the checkerboard placeholder instead.
+fn resolve_texture_data(texture: &yakui_core::paint::Texture) -> Option<(TextureFormat, &[u8])> {
+    let format = match texture.format() {
         yakui_core::paint::TextureFormat::Rgba8Srgb => TextureFormat::RGBA8,
         yakui_core::paint::TextureFormat::R8 => TextureFormat::Alpha,
-        _ => panic!(
-            "[yakui-miniquad]: got unexpected texture format: {:?}",
-            format
-        ),
+        _ => return None,
+    };
+
+    let size = texture.size();
+    if texture.data().len() != format.size(size.x, size.y) as usize {
+        return None;
+    }
+
+    Some((format, texture.data()))
+}
+
+/// The size of the squares making up the placeholder for textures which can't be uploaded.
+const PLACEHOLDER_SQUARE_SIZE: u32 = 8;
+
+/// Returns a magenta and black checkerboard in RGBA8, to stand in for a texture which can't be uploaded.
+fn make_placeholder_data(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::with_capacity(TextureFormat::RGBA8.size(width, height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let square = x / PLACEHOLDER_SQUARE_SIZE + y / PLACEHOLDER_SQUARE_SIZE;
+            let pixel = if square % 2 == 1 {
+                [0, 0, 0, 255]
+            } else {
+                [255, 0, 255, 255]
+            };
+            data.extend_from_slice(&pixel);
+        }
+    }
+    data
+}
+
+impl<B: GraphicsBackend + ?Sized> YakuiMiniquadState<B> {
+    /// Creates the texture for a yakui texture, sampled the way it asks for unless overridden.
+    fn make_managed_texture(
+        &self,
+        ctx: &mut B,
+        id: yakui_core::TextureId,
+        texture: &yakui_core::paint::Texture,
+    ) -> TextureEntry<B::Texture> {
+        let requested_sampling = TextureSampling::of_texture(texture);
+        TextureEntry::Managed {
+            texture: make_texture(ctx, texture, self.sampling_for(id, requested_sampling)),
+            requested_sampling,
+        }
     }
 }
 
-fn make_texture(ctx: &mut Context, texture: &yakui_core::paint::Texture) -> TextureId {
-    let texture_format = resolve_texture_format(texture.format());
+fn make_texture<B: GraphicsBackend + ?Sized>(
+    ctx: &mut B,
+    texture: &yakui_core::paint::Texture,
+    sampling: TextureSampling,
+) -> B::Texture {
     let dimensions = texture.size();
+    let placeholder;
+    let (texture_format, data) = match resolve_texture_data(texture) {
+        Some(resolved) => resolved,
+        None => {
+            log::error!(
+                "[yakui-miniquad]: can't upload {}x{} texture in format {:?} with {} bytes, drawing a placeholder instead",
+                dimensions.x,
+                dimensions.y,
+                texture.format(),
+                texture.data().len()
+            );
+            placeholder = make_placeholder_data(dimensions.x, dimensions.y);
+            (TextureFormat::RGBA8, placeholder.as_slice())
+        }
+    };
 
-    let id = ctx.new_texture_from_data_and_format(
-        texture.data(),
+    let id = ctx.new_texture(
+        TextureAccess::Static,
+        TextureSource::Bytes(data),
         TextureParams {
             kind: TextureKind::Texture2D,
             format: texture_format,
+            wrap: sampling.wrap,
+            min_filter: sampling.min_filter,
+            mag_filter: sampling.mag_filter,
+            width: dimensions.x,
+            height: dimensions.y,
+            mipmap_filter: sampling.mipmap_filter,
+            allocate_mipmaps: sampling.uses_mipmaps(),
+            sample_count: 0,
+        },
+    );
+    if sampling.uses_mipmaps() {
+        ctx.texture_generate_mipmaps(id);
+    }
+    id
+}
+
+fn make_render_target<B: GraphicsBackend + ?Sized>(
+    ctx: &mut B,
+    width: u32,
+    height: u32,
+) -> RenderTarget<B::Texture, B::RenderPass> {
+    let texture = ctx.new_texture(
+        TextureAccess::RenderTarget,
+        TextureSource::Empty,
+        TextureParams {
+            kind: TextureKind::Texture2D,
+            format: TextureFormat::RGBA8,
             wrap: TextureWrap::Clamp,
             min_filter: FilterMode::Linear,
             mag_filter: FilterMode::Linear,
-            width: dimensions.x,
-            height: dimensions.y,
+            width,
+            height,
             mipmap_filter: MipmapFilterMode::None,
             allocate_mipmaps: false,
             sample_count: 0,
         },
     );
-    id
+    let pass = ctx.new_render_pass(texture);
+
+    RenderTarget {
+        texture,
+        pass,
+        width,
+        height,
+    }
 }
 
 fn make_alpha_blend_state() -> BlendState {
@@ -759,15 +2294,18 @@ fn make_premultiplied_alpha_blend_state() -> BlendState {
     )
 }
 
-fn make_main_pipeline(
-    ctx: &mut Context,
+fn make_main_pipeline<B: GraphicsBackend + ?Sized>(
+    ctx: &mut B,
     buffers: &[BufferLayout],
-    attributes: &[VertexAttribute],
-) -> Pipeline {
+    vertex_layout: VertexLayout,
+) -> B::Pipeline {
     let main_shader = ctx
         .new_shader(
             ShaderSource::Glsl {
-                vertex: yakui_shader_main::VERTEX,
+                vertex: match vertex_layout {
+                    VertexLayout::Standard => yakui_shader_main::VERTEX,
+                    VertexLayout::Compact => yakui_shader_main::COMPACT_VERTEX,
+                },
                 fragment: yakui_shader_main::FRAGMENT,
             },
             yakui_shader_main::meta(),
@@ -787,18 +2325,26 @@ fn make_main_pipeline(
         primitive_type: PrimitiveType::Triangles,
     };
 
-    ctx.new_pipeline(buffers, attributes, main_shader, pipeline_params)
+    ctx.new_pipeline(
+        buffers,
+        vertex_layout.attributes(),
+        main_shader,
+        pipeline_params,
+    )
 }
 
-fn make_text_pipeline(
-    ctx: &mut Context,
+fn make_text_pipeline<B: GraphicsBackend + ?Sized>(
+    ctx: &mut B,
     buffers: &[BufferLayout],
-    attributes: &[VertexAttribute],
-) -> Pipeline {
+    vertex_layout: VertexLayout,
+) -> B::Pipeline {
     let text_shader = ctx
         .new_shader(
             ShaderSource::Glsl {
-                vertex: yakui_shader_text::VERTEX,
+                vertex: match vertex_layout {
+                    VertexLayout::Standard => yakui_shader_text::VERTEX,
+                    VertexLayout::Compact => yakui_shader_text::COMPACT_VERTEX,
+                },
                 fragment: yakui_shader_text::FRAGMENT,
             },
             yakui_shader_text::meta(),
@@ -818,7 +2364,58 @@ fn make_text_pipeline(
         primitive_type: PrimitiveType::Triangles,
     };
 
-    ctx.new_pipeline(buffers, attributes, text_shader, pipeline_params)
+    ctx.new_pipeline(
+        buffers,
+        vertex_layout.attributes(),
+        text_shader,
+        pipeline_params,
+    )
+}
+
+/// The exact sRGB transfer functions, shared by the fragment shaders of the built-in pipelines.
+macro_rules! srgb_functions {
+    () => {
+        r#"
+    vec3 srgb_to_linear(vec3 color) {
+        vec3 low = color / 12.92;
+        vec3 high = pow((color + 0.055) / 1.055, vec3(2.4));
+        return mix(low, high, step(vec3(0.04045), color));
+    }
+
+    vec3 linear_to_srgb(vec3 color) {
+        vec3 low = color * 12.92;
+        vec3 high = 1.055 * pow(max(color, vec3(0.0)), vec3(1.0 / 2.4)) - 0.055;
+        return mix(low, high, step(vec3(0.0031308), color));
+    }
+    "#
+    };
+}
+
+/// The vertex shader of the built-in pipelines for [`VertexLayout::Compact`], which decodes the sRGB vertex colors.
+macro_rules! compact_vertex_shader {
+    () => {
+        concat!(
+            r#"#version 100
+    attribute vec2 in_pos;
+    attribute vec2 in_texcoord;
+    attribute vec4 in_color;
+
+    varying lowp vec2 out_texcoord;
+    varying lowp vec4 out_color;
+    "#,
+            srgb_functions!(),
+            r#"
+    void main() {
+        lowp vec2 adjusted = in_pos * vec2(2.0, -2.0) + vec2(-1.0, 1.0);
+        gl_Position = vec4(adjusted, 0, 1);
+        out_texcoord = in_texcoord;
+
+        // the color bytes aren't normalized, and are sRGB rather than linear
+        vec4 color = in_color / 255.0;
+        out_color = vec4(srgb_to_linear(color.rgb), color.a);
+    }"#
+        )
+    };
 }
 
 mod yakui_shader_main {
@@ -840,26 +2437,37 @@ mod yakui_shader_main {
         out_color = in_color;
     }"#;
 
-    pub const FRAGMENT: &str = r#"#version 100
+    pub const COMPACT_VERTEX: &str = compact_vertex_shader!();
+
+    pub const FRAGMENT: &str = concat!(
+        r#"#version 100
+    precision mediump float;
+
     varying lowp vec2 out_texcoord;
     varying lowp vec4 out_color;
 
     uniform sampler2D color_texture;
-
+    uniform float output_srgb;
+    "#,
+        srgb_functions!(),
+        r#"
     void main() {
-        lowp vec4 color = texture2D(color_texture, out_texcoord);
+        vec4 color = texture2D(color_texture, out_texcoord);
 
-        lowp vec4 modulated = out_color * color;
-        lowp float gamma = 2.2; // apply gamma correction
-        lowp vec3 gamma_corrected = pow(modulated.rgb, vec3(1.0 / gamma));
-        
-        gl_FragColor = vec4(gamma_corrected, modulated.a);
-    }"#;
+        // textures are sRGB, while yakui hands out linear vertex colors
+        vec4 modulated = out_color * vec4(srgb_to_linear(color.rgb), color.a);
+        vec3 output_color = mix(modulated.rgb, linear_to_srgb(modulated.rgb), output_srgb);
+
+        gl_FragColor = vec4(output_color, modulated.a);
+    }"#
+    );
 
     pub fn meta() -> ShaderMeta {
         ShaderMeta {
             images: vec!["color_texture".to_string()],
-            uniforms: UniformBlockLayout { uniforms: vec![] },
+            uniforms: UniformBlockLayout {
+                uniforms: vec![UniformDesc::new("output_srgb", UniformType::Float1)],
+            },
         }
     }
 }
@@ -883,25 +2491,133 @@ mod yakui_shader_text {
         out_color = in_color;
     }"#;
 
-    pub const FRAGMENT: &str = r#"#version 100
+    pub const COMPACT_VERTEX: &str = compact_vertex_shader!();
+
+    pub const FRAGMENT: &str = concat!(
+        r#"#version 100
+    precision mediump float;
+
     varying lowp vec2 out_texcoord;
     varying lowp vec4 out_color;
 
     uniform sampler2D coverage_texture;
-
+    uniform float output_srgb;
+    "#,
+        srgb_functions!(),
+        r#"
     void main() {
-        lowp float coverage = texture2D(coverage_texture, out_texcoord).a;
-        lowp float alpha = coverage * out_color.a;
+        float coverage = texture2D(coverage_texture, out_texcoord).a;
+        float alpha = coverage * out_color.a;
 
-        lowp float gamma = 2.2; // apply gamma correction
-        lowp vec3 gamma_corrected = pow(out_color.rgb, vec3(1.0 / gamma));
-        gl_FragColor = vec4(gamma_corrected * alpha, alpha);
-    }"#;
+        vec3 output_color = mix(out_color.rgb, linear_to_srgb(out_color.rgb), output_srgb);
+        gl_FragColor = vec4(output_color * alpha, alpha);
+    }"#
+    );
 
     pub fn meta() -> ShaderMeta {
         ShaderMeta {
             images: vec!["coverage_texture".to_string()],
-            uniforms: UniformBlockLayout { uniforms: vec![] },
+            uniforms: UniformBlockLayout {
+                uniforms: vec![UniformDesc::new("output_srgb", UniformType::Float1)],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use yakui_core::geometry::{Color, Rect, Vec2};
+    use yakui_core::paint::Pipeline;
+
+    use super::*;
+    use crate::backend::{RecordedCommand, RecordingBackend};
+
+    /// Paints a box and some text with yakui's text pipeline missing, as yakui has no pipelines without a built-in
+    /// one yet, returning the pipelines applied and the draws.
+    fn paint_without_text_pipeline(behaviour: UnknownPipelineBehaviour) -> Vec<RecordedCommand> {
+        let mut ui = yakui_core::Yakui::new();
+        ui.set_surface_size(Vec2::new(640.0, 480.0));
+        ui.set_unscaled_viewport(Rect::from_pos_size(Vec2::ZERO, Vec2::new(640.0, 480.0)));
+
+        ui.start();
+        yakui::column(|| {
+            yakui::colored_box(Color::BLUE, [100.0, 20.0]);
+            yakui::text(16.0, "hi");
+        });
+        ui.finish();
+
+        let mut backend = RecordingBackend::new();
+        let mut state = YakuiMiniquadState::new(&mut backend);
+        state.set_unknown_pipeline_behaviour(behaviour);
+        state
+            .renderer()
+            .resources
+            .borrow_mut()
+            .builtin_pipelines
+            .remove(&Pipeline::Text);
+
+        // painting twice reports the unknown pipeline once
+        for _ in 0..2 {
+            backend.take_commands();
+            state.paint(&mut backend, &mut ui);
+        }
+        assert_eq!(
+            state
+                .renderer()
+                .resources
+                .borrow()
+                .reported_unknown_pipelines
+                .iter()
+                .collect::<Vec<_>>(),
+            if behaviour == UnknownPipelineBehaviour::Error {
+                vec![&Pipeline::Text]
+            } else {
+                vec![]
+            }
+        );
+
+        backend
+            .take_commands()
+            .into_iter()
+            .filter(|command| {
+                matches!(
+                    command,
+                    RecordedCommand::ApplyPipeline(_) | RecordedCommand::Draw { .. }
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unknown_pipelines_are_skipped_or_drawn_with_the_main_pipeline() {
+        let draw = |base_element, num_elements| RecordedCommand::Draw {
+            base_element,
+            num_elements,
+            num_instances: 1,
+        };
+
+        // the box takes 6 indices, the text the rest
+        let drawn_with_main = paint_without_text_pipeline(UnknownPipelineBehaviour::DrawWithMain);
+        let [RecordedCommand::ApplyPipeline(0), box_draw, RecordedCommand::Draw {
+            base_element: 6,
+            num_elements: text_elements,
+            ..
+        }] = &drawn_with_main[..]
+        else {
+            panic!("expected the box and the text drawn with the main pipeline, got {drawn_with_main:?}");
+        };
+        assert_eq!(*box_draw, draw(0, 6));
+        assert!(*text_elements > 0);
+
+        for behaviour in [
+            UnknownPipelineBehaviour::Skip,
+            UnknownPipelineBehaviour::Error,
+        ] {
+            assert_eq!(
+                paint_without_text_pipeline(behaviour),
+                [RecordedCommand::ApplyPipeline(0), draw(0, 6)],
+                "{behaviour:?}"
+            );
         }
     }
 }
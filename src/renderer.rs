@@ -0,0 +1,153 @@
+//!
+//! The GPU resources yakui is rendered with, which several [`YakuiMiniquadState`](crate::YakuiMiniquadState)s can share.
+//!
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use miniquad::{
+    BufferLayout, Context, FilterMode, MipmapFilterMode, TextureAccess, TextureFormat, TextureKind,
+    TextureParams, TextureSource, TextureWrap,
+};
+
+use crate::backend::GraphicsBackend;
+use crate::{
+    make_main_pipeline, make_text_pipeline, CustomPipeline, UnknownPipelineBehaviour, VertexLayout,
+};
+
+/// A handle to the pipelines and default texture yakui is rendered with, so they're only created once no matter how
+/// many yakui instances are drawn.
+///
+/// Cloning it gives another handle to the same resources, and pipelines registered through any handle are used by
+/// every state sharing it. Textures stay separate per [`YakuiMiniquadState`](crate::YakuiMiniquadState).
+pub struct YakuiRenderer<B: GraphicsBackend + ?Sized = Context> {
+    pub(crate) resources: Rc<RefCell<RendererResources<B>>>,
+}
+
+pub(crate) struct RendererResources<B: GraphicsBackend + ?Sized> {
+    /// The pipelines yakui's own pipelines are drawn with unless another one is registered.
+    pub(crate) builtin_pipelines: HashMap<yakui_core::paint::Pipeline, B::Pipeline>,
+    pub(crate) custom_pipelines: HashMap<yakui_core::paint::Pipeline, CustomPipeline<B::Pipeline>>,
+    pub(crate) unknown_pipeline_behaviour: UnknownPipelineBehaviour,
+    pub(crate) reported_unknown_pipelines: HashSet<yakui_core::paint::Pipeline>,
+    pub(crate) default_texture: B::Texture,
+    pub(crate) vertex_layout: VertexLayout,
+}
+
+impl<B: GraphicsBackend + ?Sized> Clone for YakuiRenderer<B> {
+    fn clone(&self) -> Self {
+        YakuiRenderer {
+            resources: Rc::clone(&self.resources),
+        }
+    }
+}
+
+impl<B: GraphicsBackend + ?Sized> YakuiRenderer<B> {
+    pub fn new(ctx: &mut B) -> Self {
+        Self::with_vertex_layout(ctx, VertexLayout::default())
+    }
+
+    /// Creates a renderer which uploads geometry in the given vertex layout, see [`VertexLayout::Compact`].
+    pub fn with_vertex_layout(ctx: &mut B, vertex_layout: VertexLayout) -> Self {
+        let main_pipeline = make_main_pipeline(ctx, &[BufferLayout::default()], vertex_layout);
+        let text_pipeline = make_text_pipeline(ctx, &[BufferLayout::default()], vertex_layout);
+
+        let default_texture = ctx.new_texture(
+            TextureAccess::Static,
+            TextureSource::Bytes(&[255, 255, 255, 255]),
+            TextureParams {
+                kind: TextureKind::Texture2D,
+                format: TextureFormat::RGBA8,
+                wrap: TextureWrap::Clamp,
+                min_filter: FilterMode::Linear,
+                mag_filter: FilterMode::Linear,
+                width: 1,
+                height: 1,
+                mipmap_filter: MipmapFilterMode::None,
+                allocate_mipmaps: false,
+                sample_count: 0,
+            },
+        );
+
+        YakuiRenderer {
+            resources: Rc::new(RefCell::new(RendererResources {
+                builtin_pipelines: HashMap::from([
+                    (yakui_core::paint::Pipeline::Main, main_pipeline),
+                    (yakui_core::paint::Pipeline::Text, text_pipeline),
+                ]),
+                custom_pipelines: HashMap::new(),
+                unknown_pipeline_behaviour: UnknownPipelineBehaviour::default(),
+                reported_unknown_pipelines: HashSet::new(),
+                default_texture,
+                vertex_layout,
+            })),
+        }
+    }
+
+    /// Renders draw calls using the given yakui pipeline with your own miniquad pipeline, replacing the built-in one if there is one.
+    ///
+    /// The pipeline needs to be created with the attributes of [`YakuiRenderer::vertex_layout`] and a single image, which the texture of the draw call is bound to.
+    /// If it has uniforms, `uniforms` holds their bytes and is applied after the pipeline.
+    pub fn register_pipeline(
+        &self,
+        yakui_pipeline: yakui_core::paint::Pipeline,
+        pipeline: B::Pipeline,
+        uniforms: &[u8],
+    ) {
+        self.resources.borrow_mut().custom_pipelines.insert(
+            yakui_pipeline,
+            CustomPipeline {
+                pipeline,
+                uniforms: uniforms.to_vec(),
+            },
+        );
+    }
+
+    /// Updates the uniforms of a pipeline registered with [`YakuiRenderer::register_pipeline`], returns false if there is none.
+    pub fn set_pipeline_uniforms(
+        &self,
+        yakui_pipeline: yakui_core::paint::Pipeline,
+        uniforms: &[u8],
+    ) -> bool {
+        match self
+            .resources
+            .borrow_mut()
+            .custom_pipelines
+            .get_mut(&yakui_pipeline)
+        {
+            Some(custom_pipeline) => {
+                custom_pipeline.uniforms.clear();
+                custom_pipeline.uniforms.extend_from_slice(uniforms);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a pipeline registered with [`YakuiRenderer::register_pipeline`], returning it so it can be deleted.
+    pub fn unregister_pipeline(
+        &self,
+        yakui_pipeline: yakui_core::paint::Pipeline,
+    ) -> Option<B::Pipeline> {
+        self.resources
+            .borrow_mut()
+            .custom_pipelines
+            .remove(&yakui_pipeline)
+            .map(|custom_pipeline| custom_pipeline.pipeline)
+    }
+
+    /// Sets what happens to draw calls using a yakui pipeline there's neither a built-in nor a registered pipeline for.
+    pub fn set_unknown_pipeline_behaviour(&self, behaviour: UnknownPipelineBehaviour) {
+        self.resources.borrow_mut().unknown_pipeline_behaviour = behaviour;
+    }
+
+    /// Returns the layout geometry is uploaded in, which custom pipelines need to be created with.
+    pub fn vertex_layout(&self) -> VertexLayout {
+        self.resources.borrow().vertex_layout
+    }
+
+    pub(crate) fn default_texture(&self) -> B::Texture {
+        self.resources.borrow().default_texture
+    }
+}
@@ -0,0 +1,891 @@
+//!
+//! A CPU rasterizer implementing [`GraphicsBackend`], for rendering yakui without a GPU, enabled by the `software-rasterizer` feature.
+//!
+//! It emulates the main and text pipelines of [`YakuiMiniquadState`](crate::YakuiMiniquadState) along with their blend modes and scissor clipping,
+//! following the conventions of miniquad's OpenGL backend, so that the image it produces can be snapshot-tested against a known good one.
+//!
+//! ```
+//! use yakui_miniquad::software::SoftwareBackend;
+//! use yakui_miniquad::YakuiMiniquadState;
+//!
+//! let mut backend = SoftwareBackend::new(320, 240);
+//! let mut state = YakuiMiniquadState::new(&mut backend);
+//!
+//! let mut yakui = yakui_core::Yakui::new();
+//! yakui.set_surface_size([320.0, 240.0].into());
+//! yakui.set_unscaled_viewport(yakui_core::geometry::Rect::from_pos_size(
+//!     Default::default(),
+//!     [320.0, 240.0].into(),
+//! ));
+//!
+//! yakui.start();
+//! // ... build your ui here
+//! yakui.finish();
+//!
+//! backend.clear([0.0, 0.0, 0.0, 1.0]);
+//! state.paint(&mut backend, &mut yakui);
+//!
+//! // e.g. encode it with the image crate of your choice and compare it against a reference image
+//! let pixels = backend.image().as_rgba8();
+//! # assert_eq!(pixels.len(), 320 * 240 * 4);
+//! ```
+//!
+
+use miniquad::{
+    BufferLayout, BufferType, BufferUsage, FilterMode, MipmapFilterMode, PassAction,
+    PipelineParams, ShaderError, ShaderMeta, ShaderSource, TextureAccess, TextureFormat,
+    TextureParams, TextureSource, TextureWrap, VertexAttribute, VertexFormat,
+};
+
+use crate::backend::GraphicsBackend;
+use crate::{make_alpha_blend_state, make_premultiplied_alpha_blend_state};
+
+/// An 8-bit RGBA image, stored row by row starting from the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Creates an image with every pixel set to transparent black.
+    pub fn new(width: u32, height: u32) -> RgbaImage {
+        RgbaImage {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Creates an image from existing pixel data, for example a decoded reference image.
+    pub fn from_rgba8(width: u32, height: u32, data: Vec<u8>) -> RgbaImage {
+        assert_eq!(
+            data.len(),
+            width as usize * height as usize * 4,
+            "[yakui-miniquad]: expected {}x{} rgba8 pixels",
+            width,
+            height
+        );
+        RgbaImage {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixel data, four bytes per pixel, row by row starting from the top.
+    pub fn as_rgba8(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.data[offset..offset + 4]);
+        pixel
+    }
+
+    /// Counts the pixels where any channel differs from `other` by more than `tolerance`.
+    ///
+    /// Images of different sizes differ in every pixel.
+    pub fn count_differing_pixels(&self, other: &RgbaImage, tolerance: u8) -> usize {
+        if self.width != other.width || self.height != other.height {
+            return self.data.len().max(other.data.len()) / 4;
+        }
+
+        self.data
+            .chunks_exact(4)
+            .zip(other.data.chunks_exact(4))
+            .filter(|(a, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > tolerance)
+            })
+            .count()
+    }
+}
+
+/// The shaders of yakui-miniquad that can be emulated, recognized by the images they sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SoftwareShader {
+    Main,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SoftwareBlend {
+    Replace,
+    Alpha,
+    PremultipliedAlpha,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SoftwareAttribute {
+    offset: usize,
+    format: VertexFormat,
+}
+
+#[derive(Debug)]
+struct SoftwarePipeline {
+    shader: SoftwareShader,
+    blend: SoftwareBlend,
+    stride: usize,
+    position: SoftwareAttribute,
+    texcoord: SoftwareAttribute,
+    color: SoftwareAttribute,
+}
+
+#[derive(Debug, Default)]
+struct SoftwareBuffer {
+    data: Vec<u8>,
+    element_size: usize,
+}
+
+#[derive(Debug)]
+struct SoftwareTexture {
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    wrap: TextureWrap,
+    min_filter: FilterMode,
+    mag_filter: FilterMode,
+    /// Texels expanded to rgba, regardless of the format they were uploaded in.
+    texels: Vec<[f32; 4]>,
+}
+
+impl SoftwareTexture {
+    fn upload(&mut self, bytes: &[u8]) {
+        let texel_count = self.width as usize * self.height as usize;
+        self.texels = match self.format {
+            TextureFormat::RGBA8 => bytes
+                .chunks_exact(4)
+                .map(|t| [t[0], t[1], t[2], t[3]].map(unorm))
+                .collect(),
+            TextureFormat::RGB8 => bytes
+                .chunks_exact(3)
+                .map(|t| [unorm(t[0]), unorm(t[1]), unorm(t[2]), 1.0])
+                .collect(),
+            // miniquad swizzles single channel textures so the channel ends up in alpha
+            TextureFormat::Alpha => bytes.iter().map(|&a| [0.0, 0.0, 0.0, unorm(a)]).collect(),
+            format => panic!(
+                "[yakui-miniquad]: software rasterizer can't sample textures of format {:?}",
+                format
+            ),
+        };
+        self.texels.resize(texel_count, [0.0; 4]);
+    }
+
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let (x, y) = match self.wrap {
+            TextureWrap::Repeat => (
+                x.rem_euclid(self.width as i64),
+                y.rem_euclid(self.height as i64),
+            ),
+            TextureWrap::Mirror => (mirror(x, self.width as i64), mirror(y, self.height as i64)),
+            TextureWrap::Clamp => (
+                x.clamp(0, self.width as i64 - 1),
+                y.clamp(0, self.height as i64 - 1),
+            ),
+        };
+        self.texels[y as usize * self.width as usize + x as usize]
+    }
+
+    fn sample(&self, u: f32, v: f32, minifying: bool) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+
+        let filter = if minifying {
+            self.min_filter
+        } else {
+            self.mag_filter
+        };
+
+        let x = u * self.width as f32;
+        let y = v * self.height as f32;
+
+        match filter {
+            FilterMode::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            FilterMode::Linear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let top = lerp4(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+                let bottom = lerp4(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+                lerp4(top, bottom, fy)
+            }
+        }
+    }
+}
+
+fn mirror(coordinate: i64, size: i64) -> i64 {
+    let period = coordinate.rem_euclid(size * 2);
+    if period < size {
+        period
+    } else {
+        size * 2 - 1 - period
+    }
+}
+
+fn unorm(value: u8) -> f32 {
+    value as f32 / 255.0
+}
+
+fn srgb_to_linear(channel: f32) -> f32 {
+    if channel < 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(channel: f32) -> f32 {
+    if channel < 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn lerp4(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ShadedVertex {
+    x: f32,
+    y: f32,
+    texcoord: [f32; 2],
+    color: [f32; 4],
+}
+
+/// A [`GraphicsBackend`] which rasterizes draw calls on the CPU into an [`RgbaImage`].
+///
+/// Only shaders of yakui-miniquad itself are supported, as they're emulated rather than executed, and scissor rects use
+/// the bottom-left origin of OpenGL.
+#[derive(Debug)]
+pub struct SoftwareBackend {
+    image: RgbaImage,
+    buffers: Vec<SoftwareBuffer>,
+    textures: Vec<SoftwareTexture>,
+    shaders: Vec<SoftwareShader>,
+    pipelines: Vec<SoftwarePipeline>,
+    /// The color texture of every render pass.
+    render_passes: Vec<usize>,
+
+    /// The render pass being drawn into along with the image of the default pass, which is set aside in the meantime.
+    render_pass: Option<(usize, RgbaImage)>,
+    pipeline: Option<usize>,
+    vertex_buffer: Option<usize>,
+    index_buffer: Option<usize>,
+    texture: Option<usize>,
+    viewport: Option<(i32, i32, i32, i32)>,
+    scissor: Option<(i32, i32, i32, i32)>,
+    /// Whether the emulated shaders encode their output to sRGB, as set by their uniforms.
+    output_srgb: bool,
+}
+
+impl SoftwareBackend {
+    /// Creates a backend rendering into a transparent image of the given size.
+    pub fn new(width: u32, height: u32) -> SoftwareBackend {
+        SoftwareBackend {
+            image: RgbaImage::new(width, height),
+            buffers: Vec::new(),
+            textures: Vec::new(),
+            shaders: Vec::new(),
+            pipelines: Vec::new(),
+            render_passes: Vec::new(),
+            render_pass: None,
+            pipeline: None,
+            vertex_buffer: None,
+            index_buffer: None,
+            texture: None,
+            viewport: None,
+            scissor: None,
+            output_srgb: true,
+        }
+    }
+
+    /// Fills the whole image with the given color and resets the scissor rect, like beginning a new render pass would.
+    pub fn clear(&mut self, color: [f32; 4]) {
+        let color = color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        for pixel in self.image.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+        self.viewport = None;
+        self.scissor = None;
+    }
+
+    /// Returns the image rendered into the default pass so far.
+    pub fn image(&self) -> &RgbaImage {
+        match &self.render_pass {
+            Some((_, image)) => image,
+            None => &self.image,
+        }
+    }
+
+    /// Returns the contents of a texture as an image, rows are stored bottom first for render targets, like in OpenGL.
+    pub fn texture_image(&self, texture: usize) -> RgbaImage {
+        let texture = &self.textures[texture];
+        let data = texture
+            .texels
+            .iter()
+            .flat_map(|texel| texel.map(|channel| (channel * 255.0).round() as u8))
+            .collect();
+        RgbaImage::from_rgba8(texture.width, texture.height, data)
+    }
+
+    /// Converts an image into the rows of a render target texture, or the other way around, as both are stored upside down relative to the other.
+    fn flip_rows(image: &RgbaImage) -> Vec<u8> {
+        let row_size = image.width as usize * 4;
+        image
+            .data
+            .chunks_exact(row_size.max(1))
+            .rev()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn shade(
+        &self,
+        pipeline: &SoftwarePipeline,
+        vertex: &ShadedVertex,
+        minifying: bool,
+    ) -> [f32; 4] {
+        let texel = match self.texture.and_then(|texture| self.textures.get(texture)) {
+            Some(texture) => texture.sample(vertex.texcoord[0], vertex.texcoord[1], minifying),
+            None => [1.0; 4],
+        };
+
+        let output = |channel: f32| {
+            if self.output_srgb {
+                linear_to_srgb(channel)
+            } else {
+                channel
+            }
+        };
+
+        match pipeline.shader {
+            SoftwareShader::Main => {
+                let texel = [
+                    srgb_to_linear(texel[0]),
+                    srgb_to_linear(texel[1]),
+                    srgb_to_linear(texel[2]),
+                    texel[3],
+                ];
+                let modulated = [0, 1, 2, 3].map(|i| vertex.color[i] * texel[i]);
+                [
+                    output(modulated[0]),
+                    output(modulated[1]),
+                    output(modulated[2]),
+                    modulated[3],
+                ]
+            }
+            SoftwareShader::Text => {
+                let alpha = texel[3] * vertex.color[3];
+                [
+                    output(vertex.color[0]) * alpha,
+                    output(vertex.color[1]) * alpha,
+                    output(vertex.color[2]) * alpha,
+                    alpha,
+                ]
+            }
+        }
+    }
+
+    fn blend(&mut self, blend: SoftwareBlend, x: u32, y: u32, source: [f32; 4]) {
+        let offset = (y as usize * self.image.width as usize + x as usize) * 4;
+        let pixel = &mut self.image.data[offset..offset + 4];
+        let destination = [pixel[0], pixel[1], pixel[2], pixel[3]].map(unorm);
+        let source = source.map(|channel| channel.clamp(0.0, 1.0));
+        let source_alpha = source[3];
+
+        let blended = match blend {
+            SoftwareBlend::Replace => source,
+            SoftwareBlend::Alpha => [0, 1, 2, 3]
+                .map(|i| source[i] * source_alpha + destination[i] * (1.0 - source_alpha)),
+            SoftwareBlend::PremultipliedAlpha => {
+                [0, 1, 2, 3].map(|i| source[i] + destination[i] * (1.0 - source_alpha))
+            }
+        };
+
+        for (channel, value) in pixel.iter_mut().zip(blended) {
+            *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+
+    fn read_vertex(&self, pipeline: &SoftwarePipeline, data: &[u8], index: usize) -> ShadedVertex {
+        let start = index * pipeline.stride;
+        let vertex = &data[start..start + pipeline.stride];
+
+        let position = read_attribute(vertex, pipeline.position);
+        let texcoord = read_attribute(vertex, pipeline.texcoord);
+        let mut color = read_attribute(vertex, pipeline.color);
+        if pipeline.color.format == VertexFormat::Byte4 {
+            // the compact vertex shader decodes sRGB bytes into linear colors
+            color = [
+                srgb_to_linear(color[0] / 255.0),
+                srgb_to_linear(color[1] / 255.0),
+                srgb_to_linear(color[2] / 255.0),
+                color[3] / 255.0,
+            ];
+        }
+
+        // same transform as the vertex shaders, but into pixels with the origin at the top
+        let (x, y, w, h) =
+            self.viewport
+                .unwrap_or((0, 0, self.image.width as i32, self.image.height as i32));
+        let top = self.image.height as i32 - (y + h);
+        ShadedVertex {
+            x: x as f32 + position[0] * w as f32,
+            y: top as f32 + position[1] * h as f32,
+            texcoord: [texcoord[0], texcoord[1]],
+            color,
+        }
+    }
+
+    fn rasterize_triangle(&mut self, pipeline: &SoftwarePipeline, vertices: [ShadedVertex; 3]) {
+        let [v0, mut v1, mut v2] = vertices;
+
+        let edge = |a: &ShadedVertex, b: &ShadedVertex, x: f32, y: f32| {
+            (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+        };
+
+        let mut area = edge(&v0, &v1, v2.x, v2.y);
+        if area == 0.0 {
+            return;
+        }
+        if area < 0.0 {
+            std::mem::swap(&mut v1, &mut v2);
+            area = -area;
+        }
+
+        // top-left fill rule, so that pixels on edges shared by two triangles are only drawn once
+        let is_top_left = |a: &ShadedVertex, b: &ShadedVertex| {
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            (dy == 0.0 && dx > 0.0) || dy < 0.0
+        };
+        let top_left = [
+            is_top_left(&v1, &v2),
+            is_top_left(&v2, &v0),
+            is_top_left(&v0, &v1),
+        ];
+
+        // the derivatives of the texture coordinates decide between minification and magnification
+        let minifying = match self.texture.and_then(|texture| self.textures.get(texture)) {
+            Some(texture) => {
+                let du_dx = ((v1.texcoord[0] - v0.texcoord[0]) * (v2.y - v0.y)
+                    - (v2.texcoord[0] - v0.texcoord[0]) * (v1.y - v0.y))
+                    / area;
+                let du_dy = ((v2.texcoord[0] - v0.texcoord[0]) * (v1.x - v0.x)
+                    - (v1.texcoord[0] - v0.texcoord[0]) * (v2.x - v0.x))
+                    / area;
+                let dv_dx = ((v1.texcoord[1] - v0.texcoord[1]) * (v2.y - v0.y)
+                    - (v2.texcoord[1] - v0.texcoord[1]) * (v1.y - v0.y))
+                    / area;
+                let dv_dy = ((v2.texcoord[1] - v0.texcoord[1]) * (v1.x - v0.x)
+                    - (v1.texcoord[1] - v0.texcoord[1]) * (v2.x - v0.x))
+                    / area;
+
+                let (width, height) = (texture.width as f32, texture.height as f32);
+                let along_x = (du_dx * width).hypot(dv_dx * height);
+                let along_y = (du_dy * width).hypot(dv_dy * height);
+                along_x.max(along_y) > 1.0
+            }
+            None => false,
+        };
+
+        let (width, height) = (self.image.width as i64, self.image.height as i64);
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (0, 0, width, height);
+
+        if let Some((x, y, w, h)) = self.scissor {
+            // scissor rects are relative to the bottom-left corner, like in OpenGL
+            let top = height - (y as i64 + h as i64);
+            min_x = min_x.max(x as i64);
+            max_x = max_x.min(x as i64 + w as i64);
+            min_y = min_y.max(top);
+            max_y = max_y.min(top + h as i64);
+        }
+
+        min_x = min_x.max(v0.x.min(v1.x).min(v2.x).floor() as i64);
+        min_y = min_y.max(v0.y.min(v1.y).min(v2.y).floor() as i64);
+        max_x = max_x.min(v0.x.max(v1.x).max(v2.x).ceil() as i64);
+        max_y = max_y.min(v0.y.max(v1.y).max(v2.y).ceil() as i64);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let weights = [
+                    edge(&v1, &v2, px, py),
+                    edge(&v2, &v0, px, py),
+                    edge(&v0, &v1, px, py),
+                ];
+
+                let inside = weights
+                    .iter()
+                    .zip(top_left)
+                    .all(|(&weight, top_left)| weight > 0.0 || (weight == 0.0 && top_left));
+                if !inside {
+                    continue;
+                }
+
+                let [l0, l1, l2] = weights.map(|weight| weight / area);
+                let interpolated = ShadedVertex {
+                    x: px,
+                    y: py,
+                    texcoord: [0, 1]
+                        .map(|i| v0.texcoord[i] * l0 + v1.texcoord[i] * l1 + v2.texcoord[i] * l2),
+                    color: [0, 1, 2, 3]
+                        .map(|i| v0.color[i] * l0 + v1.color[i] * l1 + v2.color[i] * l2),
+                };
+
+                let color = self.shade(pipeline, &interpolated, minifying);
+                self.blend(pipeline.blend, x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+fn read_attribute(vertex: &[u8], attribute: SoftwareAttribute) -> [f32; 4] {
+    let bytes = &vertex[attribute.offset..];
+    let components = attribute.format.components() as usize;
+
+    let mut value = [0.0, 0.0, 0.0, 1.0];
+    for (i, component) in value.iter_mut().enumerate().take(components.min(4)) {
+        *component = match attribute.format {
+            VertexFormat::Float1
+            | VertexFormat::Float2
+            | VertexFormat::Float3
+            | VertexFormat::Float4 => f32::from_ne_bytes([
+                bytes[i * 4],
+                bytes[i * 4 + 1],
+                bytes[i * 4 + 2],
+                bytes[i * 4 + 3],
+            ]),
+            VertexFormat::Byte1
+            | VertexFormat::Byte2
+            | VertexFormat::Byte3
+            | VertexFormat::Byte4 => bytes[i] as f32,
+            VertexFormat::Short1
+            | VertexFormat::Short2
+            | VertexFormat::Short3
+            | VertexFormat::Short4 => u16::from_ne_bytes([bytes[i * 2], bytes[i * 2 + 1]]) as f32,
+            _ => panic!(
+                "[yakui-miniquad]: software rasterizer can't read vertex attributes of format {:?}",
+                attribute.format
+            ),
+        };
+    }
+    value
+}
+
+impl GraphicsBackend for SoftwareBackend {
+    type Buffer = usize;
+    type Texture = usize;
+    type Shader = usize;
+    type Pipeline = usize;
+    type RenderPass = usize;
+
+    fn new_shader(
+        &mut self,
+        _shader: ShaderSource,
+        meta: ShaderMeta,
+    ) -> Result<Self::Shader, ShaderError> {
+        let shader = match meta.images.first().map(String::as_str) {
+            Some("color_texture") => SoftwareShader::Main,
+            Some("coverage_texture") => SoftwareShader::Text,
+            _ => {
+                return Err(ShaderError::LinkError(format!(
+                    "[yakui-miniquad]: software rasterizer can't emulate a shader sampling {:?}",
+                    meta.images
+                )))
+            }
+        };
+
+        self.shaders.push(shader);
+        Ok(self.shaders.len() - 1)
+    }
+
+    fn new_pipeline(
+        &mut self,
+        buffer_layout: &[BufferLayout],
+        attributes: &[VertexAttribute],
+        shader: Self::Shader,
+        params: PipelineParams,
+    ) -> Self::Pipeline {
+        let blend = match params.color_blend {
+            None => SoftwareBlend::Replace,
+            Some(state) if state == make_alpha_blend_state() => SoftwareBlend::Alpha,
+            Some(state) if state == make_premultiplied_alpha_blend_state() => {
+                SoftwareBlend::PremultipliedAlpha
+            }
+            Some(state) => panic!(
+                "[yakui-miniquad]: software rasterizer can't emulate blend state {:?}",
+                state
+            ),
+        };
+
+        let find = |name: &str| {
+            let mut attribute_offset = 0;
+            for attribute in attributes {
+                if attribute.name == name {
+                    return SoftwareAttribute {
+                        offset: attribute_offset,
+                        format: attribute.format,
+                    };
+                }
+                attribute_offset += attribute.format.size_bytes() as usize;
+            }
+            panic!(
+                "[yakui-miniquad]: software rasterizer needs a vertex attribute named {}",
+                name
+            )
+        };
+
+        let position = find("in_pos");
+        let texcoord = find("in_texcoord");
+        let color = find("in_color");
+
+        let stride = match buffer_layout.first() {
+            Some(layout) if layout.stride > 0 => layout.stride as usize,
+            _ => attributes
+                .iter()
+                .map(|attribute| attribute.format.size_bytes() as usize)
+                .sum(),
+        };
+
+        self.pipelines.push(SoftwarePipeline {
+            shader: self.shaders[shader],
+            blend,
+            stride,
+            position,
+            texcoord,
+            color,
+        });
+        self.pipelines.len() - 1
+    }
+
+    fn apply_pipeline(&mut self, pipeline: &Self::Pipeline) {
+        self.pipeline = Some(*pipeline);
+    }
+
+    fn apply_uniforms(&mut self, bytes: &[u8]) {
+        // the only uniform of the emulated shaders is whether to output sRGB
+        if let Some(output_srgb) = bytes.get(..4) {
+            self.output_srgb = f32::from_ne_bytes(output_srgb.try_into().unwrap()) != 0.0;
+        }
+    }
+
+    fn new_buffer(
+        &mut self,
+        _buffer_type: BufferType,
+        _usage: BufferUsage,
+        size: usize,
+        element_size: usize,
+    ) -> Self::Buffer {
+        self.buffers.push(SoftwareBuffer {
+            data: vec![0; size],
+            element_size,
+        });
+        self.buffers.len() - 1
+    }
+
+    fn buffer_update(&mut self, buffer: Self::Buffer, bytes: &[u8], element_size: usize) {
+        let buffer = &mut self.buffers[buffer];
+        buffer.data[..bytes.len()].copy_from_slice(bytes);
+        buffer.element_size = element_size;
+    }
+
+    fn buffer_size(&mut self, buffer: Self::Buffer) -> usize {
+        self.buffers[buffer].data.len()
+    }
+
+    fn delete_buffer(&mut self, buffer: Self::Buffer) {
+        self.buffers[buffer] = SoftwareBuffer::default();
+    }
+
+    fn new_texture(
+        &mut self,
+        _access: TextureAccess,
+        source: TextureSource,
+        params: TextureParams,
+    ) -> Self::Texture {
+        let mut texture = SoftwareTexture {
+            width: params.width,
+            height: params.height,
+            format: params.format,
+            wrap: params.wrap,
+            min_filter: params.min_filter,
+            mag_filter: params.mag_filter,
+            texels: Vec::new(),
+        };
+
+        match source {
+            TextureSource::Bytes(bytes) => texture.upload(bytes),
+            _ => texture.upload(&[]),
+        }
+
+        self.textures.push(texture);
+        self.textures.len() - 1
+    }
+
+    fn texture_update(&mut self, texture: Self::Texture, bytes: &[u8]) {
+        self.textures[texture].upload(bytes);
+    }
+
+    fn delete_texture(&mut self, texture: Self::Texture) {
+        self.textures[texture].texels = Vec::new();
+    }
+
+    fn texture_set_min_filter(
+        &mut self,
+        texture: Self::Texture,
+        filter: FilterMode,
+        _mipmap_filter: MipmapFilterMode,
+    ) {
+        // mipmaps aren't emulated, minified textures are sampled from the full image
+        self.textures[texture].min_filter = filter;
+    }
+
+    fn texture_set_mag_filter(&mut self, texture: Self::Texture, filter: FilterMode) {
+        self.textures[texture].mag_filter = filter;
+    }
+
+    fn texture_set_wrap(
+        &mut self,
+        texture: Self::Texture,
+        wrap_x: TextureWrap,
+        _wrap_y: TextureWrap,
+    ) {
+        // yakui-miniquad always wraps both axes the same way
+        self.textures[texture].wrap = wrap_x;
+    }
+
+    fn texture_generate_mipmaps(&mut self, _texture: Self::Texture) {}
+
+    fn texture_params(&self, texture: Self::Texture) -> TextureParams {
+        let texture = &self.textures[texture];
+        TextureParams {
+            format: texture.format,
+            wrap: texture.wrap,
+            min_filter: texture.min_filter,
+            mag_filter: texture.mag_filter,
+            width: texture.width,
+            height: texture.height,
+            ..Default::default()
+        }
+    }
+
+    fn new_render_pass(&mut self, color_texture: Self::Texture) -> Self::RenderPass {
+        self.render_passes.push(color_texture);
+        self.render_passes.len() - 1
+    }
+
+    fn delete_render_pass(&mut self, _pass: Self::RenderPass) {}
+
+    fn begin_pass(&mut self, pass: Option<Self::RenderPass>, action: PassAction) {
+        if let Some(pass) = pass {
+            let texture = self.render_passes[pass];
+            let contents = self.texture_image(texture);
+            let image = RgbaImage::from_rgba8(
+                contents.width,
+                contents.height,
+                SoftwareBackend::flip_rows(&contents),
+            );
+            let default_image = std::mem::replace(&mut self.image, image);
+            self.render_pass = Some((pass, default_image));
+        }
+
+        match action {
+            PassAction::Clear {
+                color: Some((r, g, b, a)),
+                ..
+            } => self.clear([r, g, b, a]),
+            _ => {
+                self.viewport = None;
+                self.scissor = None;
+            }
+        }
+    }
+
+    fn end_render_pass(&mut self) {
+        if let Some((pass, default_image)) = self.render_pass.take() {
+            let image = std::mem::replace(&mut self.image, default_image);
+            let texture = &mut self.textures[self.render_passes[pass]];
+            texture.format = TextureFormat::RGBA8;
+            texture.upload(&SoftwareBackend::flip_rows(&image));
+        }
+    }
+
+    fn commit_frame(&mut self) {
+        // the image is what was drawn, there's nothing to present it to
+    }
+
+    fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.viewport = Some((x, y, w, h));
+    }
+
+    fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.scissor = Some((x, y, w, h));
+    }
+
+    fn apply_bindings(
+        &mut self,
+        vertex_buffers: &[Self::Buffer],
+        index_buffer: Self::Buffer,
+        textures: &[Self::Texture],
+    ) {
+        self.vertex_buffer = vertex_buffers.first().copied();
+        self.index_buffer = Some(index_buffer);
+        self.texture = textures.first().copied();
+    }
+
+    fn draw(&mut self, base_element: i32, num_elements: i32, _num_instances: i32) {
+        let (Some(pipeline), Some(vertex_buffer), Some(index_buffer)) =
+            (self.pipeline, self.vertex_buffer, self.index_buffer)
+        else {
+            return;
+        };
+
+        // temporarily take the resources out, so the image can be drawn into while reading them
+        let pipelines = std::mem::take(&mut self.pipelines);
+        let vertices = std::mem::take(&mut self.buffers[vertex_buffer].data);
+        let indices = std::mem::take(&mut self.buffers[index_buffer]);
+
+        let pipeline_state = &pipelines[pipeline];
+        let read_index = |element: usize| match indices.element_size {
+            4 => u32::from_ne_bytes([
+                indices.data[element * 4],
+                indices.data[element * 4 + 1],
+                indices.data[element * 4 + 2],
+                indices.data[element * 4 + 3],
+            ]) as usize,
+            _ => u16::from_ne_bytes([indices.data[element * 2], indices.data[element * 2 + 1]])
+                as usize,
+        };
+
+        let start = base_element as usize;
+        let end = start + num_elements as usize;
+        for triangle in (start..end).step_by(3).take_while(|first| first + 3 <= end) {
+            let triangle = [triangle, triangle + 1, triangle + 2]
+                .map(|element| self.read_vertex(pipeline_state, &vertices, read_index(element)));
+            self.rasterize_triangle(pipeline_state, triangle);
+        }
+
+        self.buffers[index_buffer] = indices;
+        self.buffers[vertex_buffer].data = vertices;
+        self.pipelines = pipelines;
+    }
+}
@@ -0,0 +1,288 @@
+//!
+//! Several independent yakui instances drawn through one renderer, such as a HUD with a debug overlay on top of it.
+//!
+//! Each instance keeps its own textures, scale and viewport, while input goes to the topmost instance with a widget
+//! under the cursor, or to the one a drag or text input started in.
+//!
+
+use std::collections::HashMap;
+
+use miniquad::{Context, KeyCode, KeyMods, MouseButton, TouchPhase};
+
+use crate::backend::GraphicsBackend;
+use crate::renderer::YakuiRenderer;
+use crate::YakuiMiniQuad;
+
+/// A stack of [`YakuiMiniQuad`]s sharing one [`YakuiRenderer`], drawn bottom first.
+///
+/// Call the event-handler functions of the stack instead of those of its instances, and build the ui of each instance
+/// between its own `start` and `finish`, one after another.
+pub struct YakuiStack<B: GraphicsBackend + ?Sized = Context> {
+    renderer: YakuiRenderer<B>,
+    instances: Vec<YakuiMiniQuad<B>>,
+    /// The instance a sunk mouse press started in, which gets the cursor until every button is released.
+    pointer_owner: Option<usize>,
+    /// The instance each touch started in, or none if every instance got it.
+    touch_owners: HashMap<u64, Option<usize>>,
+}
+
+impl<B: GraphicsBackend + ?Sized> YakuiStack<B> {
+    pub fn new(ctx: &mut B) -> Self {
+        Self::with_renderer(YakuiRenderer::new(ctx))
+    }
+
+    /// Creates a stack drawing through an existing renderer, so its instances share pipelines and the default texture
+    /// with instances created outside of the stack.
+    pub fn with_renderer(renderer: YakuiRenderer<B>) -> Self {
+        YakuiStack {
+            renderer,
+            instances: Vec::new(),
+            pointer_owner: None,
+            touch_owners: HashMap::new(),
+        }
+    }
+
+    /// Returns the renderer every instance draws through, for registering custom pipelines and the like.
+    pub fn renderer(&self) -> &YakuiRenderer<B> {
+        &self.renderer
+    }
+
+    /// Adds a new instance on top of the others, returning its index.
+    pub fn push(&mut self) -> usize {
+        self.instances
+            .push(YakuiMiniQuad::with_renderer(self.renderer.clone()));
+        self.instances.len() - 1
+    }
+
+    /// Removes the instance at the given index, shifting the ones above it down.
+    ///
+    /// Its textures aren't deleted, see [`YakuiMiniquadState::drop_textures`](crate::YakuiMiniquadState::drop_textures).
+    pub fn remove(&mut self, index: usize) -> YakuiMiniQuad<B> {
+        self.pointer_owner = None;
+        self.touch_owners.clear();
+        self.instances.remove(index)
+    }
+
+    /// Returns the instance at the given index, bottom first.
+    pub fn get(&mut self, index: usize) -> Option<&mut YakuiMiniQuad<B>> {
+        self.instances.get_mut(index)
+    }
+
+    /// Returns the number of instances in the stack.
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns true if there are no instances in the stack.
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Returns true if any instance wants the mouse, see [`YakuiMiniQuad::has_mouse_focus`].
+    pub fn has_mouse_focus(&self) -> bool {
+        self.instances
+            .iter()
+            .any(|instance| instance.has_mouse_focus())
+    }
+
+    /// Returns true if any instance sunk the last keyboard event, see [`YakuiMiniQuad::has_keyboard_focus`].
+    pub fn has_keyboard_focus(&self) -> bool {
+        self.instances
+            .iter()
+            .any(|instance| instance.has_keyboard_focus())
+    }
+
+    /// Renders every instance, bottom first.
+    pub fn draw(&mut self, ctx: &mut B) {
+        for instance in &mut self.instances {
+            instance.draw(ctx);
+        }
+    }
+
+    /// Returns the topmost instance with a widget under the cursor.
+    fn topmost_under_cursor(&self) -> Option<usize> {
+        self.instances
+            .iter()
+            .rposition(|instance| instance.is_cursor_over_ui())
+    }
+
+    /// Returns the topmost instance with a focused widget taking keyboard input, as of its last frame.
+    fn keyboard_owner(&self) -> Option<usize> {
+        self.instances
+            .iter()
+            .rposition(|instance| instance.input_capture().wants_keyboard)
+    }
+
+    /// Moves the cursor out of every instance but the given one, so only it shows hover state or sees clicks land.
+    fn give_cursor_to(&mut self, owner: usize) {
+        for (index, instance) in self.instances.iter_mut().enumerate() {
+            if index != owner && instance.cursor_position.is_some() {
+                instance.move_cursor(None);
+            }
+        }
+    }
+
+    pub fn resize_event(&mut self, width: f32, height: f32) {
+        for instance in &mut self.instances {
+            instance.resize_event(width, height);
+        }
+    }
+
+    pub fn mouse_motion_event(&mut self, x: f32, y: f32) {
+        match self.pointer_owner {
+            Some(owner) => {
+                self.instances[owner].mouse_motion_event(x, y);
+                self.give_cursor_to(owner);
+            }
+            None => {
+                for instance in &mut self.instances {
+                    instance.mouse_motion_event(x, y);
+                }
+                if let Some(topmost) = self.topmost_under_cursor() {
+                    self.give_cursor_to(topmost);
+                }
+            }
+        }
+    }
+
+    /// Tells every instance the cursor left the window, see [`YakuiMiniQuad::mouse_left_window`].
+    pub fn mouse_left_window(&mut self) {
+        for instance in &mut self.instances {
+            instance.mouse_left_window();
+        }
+    }
+
+    // Mouse buttons and the wheel go to every instance, only the topmost one under the cursor has it over a widget,
+    // and the others need to see clicks outside of their widgets to let go of text inputs.
+
+    // Like the functions of the instances, these return whether any instance sunk the event.
+
+    pub fn mouse_wheel_event(&mut self, x: f32, y: f32) -> bool {
+        let mut sunk = false;
+        for instance in &mut self.instances {
+            sunk |= instance.mouse_wheel_event(x, y);
+        }
+        sunk
+    }
+
+    pub fn mouse_button_down_event(&mut self, button: MouseButton, x: f32, y: f32) -> bool {
+        let mut sunk = false;
+        for instance in &mut self.instances {
+            sunk |= instance.mouse_button_down_event(button, x, y);
+        }
+
+        if self.pointer_owner.is_none() {
+            self.pointer_owner = self
+                .instances
+                .iter()
+                .rposition(|instance| !instance.captured_buttons.is_empty());
+        }
+        sunk
+    }
+
+    pub fn mouse_button_up_event(&mut self, button: MouseButton, x: f32, y: f32) -> bool {
+        let mut sunk = false;
+        for instance in &mut self.instances {
+            sunk |= instance.mouse_button_up_event(button, x, y);
+        }
+
+        if let Some(owner) = self.pointer_owner {
+            if self.instances[owner].captured_buttons.is_empty() {
+                self.pointer_owner = None;
+            }
+        }
+        sunk
+    }
+
+    pub fn char_event(&mut self, character: char, keymods: KeyMods, repeat: bool) -> bool {
+        match self.keyboard_owner() {
+            Some(owner) => self.instances[owner].char_event(character, keymods, repeat),
+            None => {
+                let mut sunk = false;
+                for instance in &mut self.instances {
+                    sunk |= instance.char_event(character, keymods, repeat);
+                }
+                sunk
+            }
+        }
+    }
+
+    pub fn key_down_event(&mut self, keycode: KeyCode, keymods: KeyMods, repeat: bool) -> bool {
+        match self.keyboard_owner() {
+            Some(owner) => self.instances[owner].key_down_event(keycode, keymods, repeat),
+            None => {
+                let mut sunk = false;
+                for instance in &mut self.instances {
+                    sunk |= instance.key_down_event(keycode, keymods, repeat);
+                }
+                sunk
+            }
+        }
+    }
+
+    pub fn key_up_event(&mut self, keycode: KeyCode, keymods: KeyMods) -> bool {
+        // releases go everywhere, so a key pressed before focus moved can't get stuck
+        let mut sunk = false;
+        for instance in &mut self.instances {
+            sunk |= instance.key_up_event(keycode, keymods);
+        }
+        sunk
+    }
+
+    pub fn touch_event(&mut self, phase: TouchPhase, id: u64, x: f32, y: f32) -> bool {
+        let owner = match phase {
+            TouchPhase::Started => {
+                let position = yakui_core::geometry::Vec2::new(x, y);
+                let owner = self
+                    .instances
+                    .iter_mut()
+                    .rposition(|instance| instance.is_mouse_widget_at(position));
+                self.touch_owners.insert(id, owner);
+                owner
+            }
+            TouchPhase::Moved => match self.touch_owners.get(&id) {
+                Some(owner) => *owner,
+                None => return false,
+            },
+            TouchPhase::Ended | TouchPhase::Cancelled => match self.touch_owners.remove(&id) {
+                Some(owner) => owner,
+                None => return false,
+            },
+        };
+
+        match owner {
+            Some(owner) => self.instances[owner].touch_event(phase, id, x, y),
+            None => {
+                let mut sunk = false;
+                for instance in &mut self.instances {
+                    sunk |= instance.touch_event(phase, id, x, y);
+                }
+                sunk
+            }
+        }
+    }
+
+    pub fn window_minimized_event(&mut self) {
+        self.pointer_owner = None;
+        self.touch_owners.clear();
+        for instance in &mut self.instances {
+            instance.window_minimized_event();
+        }
+    }
+
+    pub fn window_restored_event(&mut self) {
+        self.pointer_owner = None;
+        self.touch_owners.clear();
+        for instance in &mut self.instances {
+            instance.window_restored_event();
+        }
+    }
+
+    pub fn quit_requested_event(&mut self) {
+        self.pointer_owner = None;
+        self.touch_owners.clear();
+        for instance in &mut self.instances {
+            instance.quit_requested_event();
+        }
+    }
+}
@@ -0,0 +1,262 @@
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use miniquad::{
+    BufferLayout, BufferType, BufferUsage, FilterMode, MipmapFilterMode, PassAction,
+    PipelineParams, ShaderError, ShaderMeta, ShaderSource, TextureAccess, TextureParams,
+    TextureSource, TextureWrap, VertexAttribute,
+};
+use yakui_core::geometry::{Color, Constraints, Vec2};
+use yakui_core::Yakui;
+
+use yakui_miniquad::backend::GraphicsBackend;
+use yakui_miniquad::YakuiMiniquadState;
+
+use common::new_ui;
+
+/// Counts the allocations of the current thread, so other tests running meanwhile don't count.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// A backend which draws nothing and only keeps what yakui-miniquad reads back, so it never allocates once its
+/// resources are created, unlike [`RecordingBackend`](yakui_miniquad::backend::RecordingBackend).
+#[derive(Default)]
+struct NullBackend {
+    buffers: Vec<usize>,
+    textures: Vec<TextureParams>,
+    handles: usize,
+}
+
+impl NullBackend {
+    fn next_handle(&mut self) -> usize {
+        self.handles += 1;
+        self.handles
+    }
+}
+
+impl GraphicsBackend for NullBackend {
+    type Buffer = usize;
+    type Texture = usize;
+    type Shader = usize;
+    type Pipeline = usize;
+    type RenderPass = usize;
+
+    fn new_shader(
+        &mut self,
+        _shader: ShaderSource,
+        _meta: ShaderMeta,
+    ) -> Result<Self::Shader, ShaderError> {
+        Ok(self.next_handle())
+    }
+
+    fn new_pipeline(
+        &mut self,
+        _buffer_layout: &[BufferLayout],
+        _attributes: &[VertexAttribute],
+        _shader: Self::Shader,
+        _params: PipelineParams,
+    ) -> Self::Pipeline {
+        self.next_handle()
+    }
+
+    fn apply_pipeline(&mut self, _pipeline: &Self::Pipeline) {}
+
+    fn apply_uniforms(&mut self, _bytes: &[u8]) {}
+
+    fn new_buffer(
+        &mut self,
+        _buffer_type: BufferType,
+        _usage: BufferUsage,
+        size: usize,
+        _element_size: usize,
+    ) -> Self::Buffer {
+        self.buffers.push(size);
+        self.buffers.len() - 1
+    }
+
+    fn buffer_update(&mut self, buffer: Self::Buffer, bytes: &[u8], _element_size: usize) {
+        assert!(bytes.len() <= self.buffers[buffer]);
+    }
+
+    fn buffer_size(&mut self, buffer: Self::Buffer) -> usize {
+        self.buffers[buffer]
+    }
+
+    fn delete_buffer(&mut self, buffer: Self::Buffer) {
+        self.buffers[buffer] = 0;
+    }
+
+    fn new_texture(
+        &mut self,
+        _access: TextureAccess,
+        _source: TextureSource,
+        params: TextureParams,
+    ) -> Self::Texture {
+        self.textures.push(params);
+        self.textures.len() - 1
+    }
+
+    fn texture_update(&mut self, _texture: Self::Texture, _bytes: &[u8]) {}
+
+    fn delete_texture(&mut self, _texture: Self::Texture) {}
+
+    fn texture_set_min_filter(
+        &mut self,
+        texture: Self::Texture,
+        filter: FilterMode,
+        mipmap_filter: MipmapFilterMode,
+    ) {
+        self.textures[texture].min_filter = filter;
+        self.textures[texture].mipmap_filter = mipmap_filter;
+    }
+
+    fn texture_set_mag_filter(&mut self, texture: Self::Texture, filter: FilterMode) {
+        self.textures[texture].mag_filter = filter;
+    }
+
+    fn texture_set_wrap(
+        &mut self,
+        texture: Self::Texture,
+        wrap_x: TextureWrap,
+        _wrap_y: TextureWrap,
+    ) {
+        self.textures[texture].wrap = wrap_x;
+    }
+
+    fn texture_generate_mipmaps(&mut self, _texture: Self::Texture) {}
+
+    fn texture_params(&self, texture: Self::Texture) -> TextureParams {
+        self.textures[texture]
+    }
+
+    fn new_render_pass(&mut self, _color_texture: Self::Texture) -> Self::RenderPass {
+        self.next_handle()
+    }
+
+    fn delete_render_pass(&mut self, _pass: Self::RenderPass) {}
+
+    fn begin_pass(&mut self, _pass: Option<Self::RenderPass>, _action: PassAction) {}
+
+    fn end_render_pass(&mut self) {}
+
+    fn commit_frame(&mut self) {}
+
+    fn apply_viewport(&mut self, _x: i32, _y: i32, _w: i32, _h: i32) {}
+
+    fn apply_scissor_rect(&mut self, _x: i32, _y: i32, _w: i32, _h: i32) {}
+
+    fn apply_bindings(
+        &mut self,
+        _vertex_buffers: &[Self::Buffer],
+        _index_buffer: Self::Buffer,
+        _textures: &[Self::Texture],
+    ) {
+    }
+
+    fn draw(&mut self, _base_element: i32, _num_elements: i32, _num_instances: i32) {}
+}
+
+/// Counts the allocations of rendering the ui, leaving out yakui's own paint pass which allocates by itself.
+fn paint_allocations(
+    state: &mut YakuiMiniquadState<NullBackend>,
+    backend: &mut NullBackend,
+    ui: &mut Yakui,
+) -> usize {
+    let viewport = ui.layout_dom().unscaled_viewport();
+    let paint = ui.paint();
+    count_allocations(|| state.paint_dom(backend, paint, viewport))
+}
+
+/// Draws the same layout every frame, with the boxes in `color` so the geometry changes but keeps its size.
+fn frame(ui: &mut Yakui, color: Color) {
+    ui.start();
+    yakui::column(|| {
+        yakui::text(16.0, "hello, world!");
+        yakui::constrained(Constraints::tight(Vec2::new(120.0, 60.0)), || {
+            yakui::scroll_vertical(|| {
+                yakui::column(|| {
+                    for _ in 0..10 {
+                        yakui::colored_box(color, [120.0, 16.0]);
+                    }
+                });
+            });
+        });
+    });
+    ui.finish();
+}
+
+#[test]
+fn stable_frames_dont_allocate() {
+    let mut ui = new_ui(160.0, 120.0);
+
+    let mut backend = NullBackend::default();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+
+    // let glyphs get cached and buffers grow to fit the ui
+    for color in [Color::RED, Color::BLUE, Color::RED] {
+        frame(&mut ui, color);
+        state.paint(&mut backend, &mut ui);
+    }
+
+    // a different colour than last frame, so the geometry is uploaded again instead of skipped
+    frame(&mut ui, Color::BLUE);
+    assert_eq!(paint_allocations(&mut state, &mut backend, &mut ui), 0);
+}
+
+/// Draws a row of boxes merged into a single call too big for 16 bit indices, in `color`.
+fn oversized_frame(ui: &mut Yakui, color: Color) {
+    ui.start();
+    yakui::row(|| {
+        for _ in 0..20_000 {
+            yakui::colored_box(color, [1.0, 1.0]);
+        }
+    });
+    ui.finish();
+}
+
+#[test]
+fn stable_frames_with_oversized_calls_dont_allocate() {
+    let mut ui = new_ui(160.0, 120.0);
+
+    let mut backend = NullBackend::default();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+
+    for color in [Color::RED, Color::BLUE, Color::RED] {
+        oversized_frame(&mut ui, color);
+        state.paint(&mut backend, &mut ui);
+    }
+    assert!(ui.paint().layers()[0].calls[0].vertices.len() > u16::MAX as usize + 1);
+
+    oversized_frame(&mut ui, Color::BLUE);
+    assert_eq!(paint_allocations(&mut state, &mut backend, &mut ui), 0);
+}
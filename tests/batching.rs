@@ -0,0 +1,191 @@
+mod common;
+
+use yakui::widgets::Layer;
+use yakui_core::geometry::{Color, Constraints, Vec2};
+use yakui_core::paint::Pipeline;
+use yakui_core::{TextureId, Yakui};
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::{VertexLayout, YakuiMiniquadState};
+
+use common::{count, drawn_positions, new_ui, painted_positions};
+
+/// A panel with a header, a scrolling list of swatches which gets clipped, and a footer.
+fn panel() -> Yakui {
+    let mut ui = new_ui(640.0, 480.0);
+
+    ui.start();
+    yakui::column(|| {
+        yakui::colored_box(Color::BLUE, [200.0, 24.0]);
+        yakui::constrained(Constraints::tight(Vec2::new(200.0, 100.0)), || {
+            yakui::scroll_vertical(|| {
+                yakui::column(|| {
+                    for _ in 0..20 {
+                        yakui::colored_box(Color::RED, [200.0, 16.0]);
+                    }
+                });
+            });
+        });
+        yakui::colored_box(Color::BLUE, [200.0, 24.0]);
+    });
+    ui.finish();
+    ui
+}
+
+#[test]
+fn pipelines_and_bindings_are_only_applied_when_they_change() {
+    let mut ui = panel();
+
+    let meshes: Vec<(Pipeline, Option<TextureId>)> = ui
+        .paint()
+        .layers()
+        .iter()
+        .flat_map(|layer| &layer.calls)
+        .map(|mesh| (mesh.pipeline, mesh.texture))
+        .collect();
+    // the meshes only differ in their clip rects, which don't need the pipeline or bindings applied again
+    let switches = 1 + meshes.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    assert!(meshes.len() > switches);
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+
+    let draws = count(backend.commands(), |command| {
+        matches!(command, RecordedCommand::Draw { .. })
+    });
+    let pipelines = count(backend.commands(), |command| {
+        matches!(command, RecordedCommand::ApplyPipeline(_))
+    });
+    let bindings = count(backend.commands(), |command| {
+        matches!(command, RecordedCommand::ApplyBindings { .. })
+    });
+
+    assert_eq!(draws, meshes.len());
+    assert_eq!(pipelines, switches);
+    assert_eq!(bindings, switches);
+}
+
+#[test]
+fn draw_calls_are_merged_across_layers() {
+    let mut ui = new_ui(640.0, 480.0);
+
+    // yakui merges the boxes of each layer into one call, but can't merge across layers
+    ui.start();
+    yakui::column(|| {
+        yakui::colored_box(Color::BLUE, [200.0, 24.0]);
+        Layer::new().show(|| {
+            yakui::colored_box(Color::RED, [200.0, 24.0]);
+        });
+    });
+    ui.finish();
+
+    let layers = ui.paint().layers();
+    let calls: Vec<_> = layers.iter().flat_map(|layer| &layer.calls).collect();
+    assert_eq!(layers.len(), 2);
+    assert_eq!(calls.len(), 2);
+    assert_eq!(
+        (calls[0].pipeline, calls[0].texture, calls[0].clip),
+        (calls[1].pipeline, calls[1].texture, calls[1].clip)
+    );
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+
+    let draws: Vec<_> = backend
+        .commands()
+        .iter()
+        .filter(|command| matches!(command, RecordedCommand::Draw { .. }))
+        .collect();
+    assert_eq!(
+        draws,
+        [&RecordedCommand::Draw {
+            base_element: 0,
+            num_elements: 12,
+            num_instances: 1,
+        }]
+    );
+}
+
+#[test]
+fn calls_which_dont_fit_in_the_current_batch_start_a_new_one() {
+    let mut ui = new_ui(640.0, 480.0);
+
+    // two calls of 40000 vertices each, which together can't be addressed with 16-bit indices
+    ui.start();
+    yakui::row(|| {
+        for _ in 0..10_000 {
+            yakui::colored_box(Color::RED, [1.0, 1.0]);
+        }
+        Layer::new().show(|| {
+            yakui::row(|| {
+                for _ in 0..10_000 {
+                    yakui::colored_box(Color::BLUE, [1.0, 1.0]);
+                }
+            });
+        });
+    });
+    ui.finish();
+
+    let layers = ui.paint().layers();
+    let calls: Vec<_> = layers.iter().flat_map(|layer| &layer.calls).collect();
+    assert_eq!(calls.len(), 2);
+    assert!(calls.iter().all(|call| call.vertices.len() == 40_000));
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+
+    // each batch has a vertex buffer of its own, numbered in the order they're first bound
+    let mut vertex_buffers: Vec<usize> = Vec::new();
+    let mut bound = None;
+    let mut batches = Vec::new();
+    for command in backend.commands() {
+        match command {
+            RecordedCommand::ApplyBindings {
+                vertex_buffers: buffers,
+                ..
+            } => bound = Some(buffers[0]),
+            RecordedCommand::Draw { .. } => {
+                let buffer = bound.expect("draw without bindings");
+                let batch = match vertex_buffers.iter().position(|&b| b == buffer) {
+                    Some(batch) => batch,
+                    None => {
+                        vertex_buffers.push(buffer);
+                        vertex_buffers.len() - 1
+                    }
+                };
+                batches.push(batch);
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(batches, [0, 1]);
+
+    let vertex_size = VertexLayout::Standard.vertex_size();
+    let vertex_counts: Vec<usize> = vertex_buffers
+        .iter()
+        .map(|&vertex_buffer| {
+            backend
+                .commands()
+                .iter()
+                .find_map(|command| match command {
+                    RecordedCommand::BufferUpdate { buffer, size } if *buffer == vertex_buffer => {
+                        Some(size / vertex_size)
+                    }
+                    _ => None,
+                })
+                .expect("batch without uploaded vertices")
+        })
+        .collect();
+    assert_eq!(vertex_counts, [40_000, 40_000]);
+
+    assert_eq!(
+        drawn_positions(&backend, vertex_size),
+        painted_positions(&mut ui)
+    );
+}
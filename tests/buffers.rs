@@ -0,0 +1,245 @@
+mod common;
+
+use yakui_core::geometry::Color;
+use yakui_core::Yakui;
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::buffers::BufferPolicy;
+use yakui_miniquad::renderer::YakuiRenderer;
+use yakui_miniquad::{VertexLayout, YakuiMiniquadState};
+
+use common::{count, new_ui};
+
+/// Paints a frame of `boxes` colored boxes, returning the commands it issued.
+fn paint_boxes(
+    backend: &mut RecordingBackend,
+    state: &mut YakuiMiniquadState<RecordingBackend>,
+    ui: &mut Yakui,
+    boxes: usize,
+) -> Vec<RecordedCommand> {
+    ui.start();
+    yakui::row(|| {
+        for _ in 0..boxes {
+            yakui::colored_box(Color::RED, [4.0, 4.0]);
+        }
+    });
+    ui.finish();
+
+    state.paint(backend, ui);
+    backend.take_commands()
+}
+
+fn new_buffers(commands: &[RecordedCommand]) -> Vec<(usize, usize)> {
+    commands
+        .iter()
+        .filter_map(|command| match command {
+            RecordedCommand::NewBuffer { buffer, size, .. } => Some((*buffer, *size)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn bound_vertex_buffers(commands: &[RecordedCommand]) -> Vec<usize> {
+    commands
+        .iter()
+        .filter_map(|command| match command {
+            RecordedCommand::ApplyBindings { vertex_buffers, .. } => Some(vertex_buffers[0]),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn slowly_growing_ui_grows_buffers_geometrically() {
+    let mut ui = new_ui(640.0, 480.0);
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+
+    let frames = 60;
+    let created: usize = (1..=frames)
+        .map(|boxes| new_buffers(&paint_boxes(&mut backend, &mut state, &mut ui, boxes)).len())
+        .sum();
+
+    // a vertex and an index buffer for a handful of the frames, rather than for every one of them
+    assert!(
+        created <= 2 * 12,
+        "created {created} buffers in {frames} frames"
+    );
+}
+
+#[test]
+fn buffers_shrink_after_a_huge_frame() {
+    let mut ui = new_ui(640.0, 480.0);
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    state.set_buffer_policy(
+        &mut backend,
+        BufferPolicy {
+            shrink_after_frames: Some(3),
+            ..BufferPolicy::default()
+        },
+    );
+    backend.take_commands();
+
+    let huge = new_buffers(&paint_boxes(&mut backend, &mut state, &mut ui, 1000));
+    let huge_size: usize = huge.iter().map(|(_, size)| size).sum();
+
+    let mut commands = Vec::new();
+    for _ in 0..3 {
+        commands.extend(paint_boxes(&mut backend, &mut state, &mut ui, 1));
+    }
+
+    let shrunk = new_buffers(&commands);
+    let shrunk_size: usize = shrunk.iter().map(|(_, size)| size).sum();
+    for (buffer, _) in &huge {
+        assert!(commands.contains(&RecordedCommand::DeleteBuffer(*buffer)));
+    }
+    assert_eq!(shrunk.len(), huge.len());
+    assert!(shrunk_size * 100 < huge_size, "{shrunk:?} after {huge:?}");
+}
+
+#[test]
+fn frames_in_flight_rotate_between_buffers() {
+    let mut ui = new_ui(640.0, 480.0);
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    state.set_buffer_policy(
+        &mut backend,
+        BufferPolicy {
+            frames_in_flight: 2,
+            ..BufferPolicy::default()
+        },
+    );
+
+    // the ui changes every frame, as unchanged frames are drawn from the buffers they were uploaded into already
+    let bound: Vec<Vec<usize>> = (0..4)
+        .map(|frame| {
+            bound_vertex_buffers(&paint_boxes(
+                &mut backend,
+                &mut state,
+                &mut ui,
+                5 - frame % 2,
+            ))
+        })
+        .collect();
+
+    assert_ne!(bound[0], bound[1]);
+    assert_eq!(bound[0], bound[2]);
+    assert_eq!(bound[1], bound[3]);
+}
+
+#[test]
+fn dropping_buffers_deletes_every_buffer_created() {
+    let mut ui = new_ui(640.0, 480.0);
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    state.set_buffer_policy(
+        &mut backend,
+        BufferPolicy {
+            frames_in_flight: 3,
+            ..BufferPolicy::default()
+        },
+    );
+    backend.take_commands();
+
+    let mut commands = Vec::new();
+    for boxes in [10, 500, 20, 1] {
+        commands.extend(paint_boxes(&mut backend, &mut state, &mut ui, boxes));
+    }
+    state.drop_buffers(&mut backend);
+    commands.extend(backend.take_commands());
+
+    for (buffer, _) in new_buffers(&commands) {
+        assert!(
+            commands.contains(&RecordedCommand::DeleteBuffer(buffer)),
+            "buffer {buffer} was never deleted"
+        );
+    }
+}
+
+#[test]
+fn unchanged_frames_are_drawn_without_uploading() {
+    let mut ui = new_ui(640.0, 480.0);
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+
+    let is_upload =
+        |command: &RecordedCommand| matches!(command, RecordedCommand::BufferUpdate { .. });
+    let is_draw = |command: &RecordedCommand| matches!(command, RecordedCommand::Draw { .. });
+
+    let first = paint_boxes(&mut backend, &mut state, &mut ui, 8);
+    let unchanged = paint_boxes(&mut backend, &mut state, &mut ui, 8);
+    let changed = paint_boxes(&mut backend, &mut state, &mut ui, 9);
+
+    assert!(first.iter().any(is_upload));
+    assert!(!unchanged.iter().any(is_upload));
+    assert_eq!(count(&unchanged, is_draw), count(&first, is_draw));
+    assert!(changed.iter().any(is_upload));
+}
+
+#[test]
+fn compact_vertices_are_smaller() {
+    let vertex_bytes = |vertex_layout: VertexLayout| {
+        let mut ui = new_ui(640.0, 480.0);
+        let mut backend = RecordingBackend::new();
+        let mut state = YakuiMiniquadState::with_renderer(YakuiRenderer::with_vertex_layout(
+            &mut backend,
+            vertex_layout,
+        ));
+        let commands = paint_boxes(&mut backend, &mut state, &mut ui, 10);
+
+        // the vertex buffer is created first, and uploaded into first
+        commands
+            .iter()
+            .find_map(|command| match command {
+                RecordedCommand::BufferUpdate { size, .. } => Some(*size),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    let standard = vertex_bytes(VertexLayout::Standard);
+    let compact = vertex_bytes(VertexLayout::Compact);
+
+    assert_eq!(standard, 10 * 4 * VertexLayout::Standard.vertex_size());
+    assert_eq!(compact, 10 * 4 * VertexLayout::Compact.vertex_size());
+    assert_eq!(VertexLayout::Compact.vertex_size(), 20);
+}
+
+#[test]
+fn large_unchanged_frames_are_drawn_without_uploading_until_one_box_changes() {
+    const BOXES: usize = 50_000;
+
+    let mut ui = new_ui(640.0, 480.0);
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+
+    let mut paint = |recolored: Option<usize>| {
+        ui.start();
+        yakui::row(|| {
+            for i in 0..BOXES {
+                let color = if recolored == Some(i) {
+                    Color::BLUE
+                } else {
+                    Color::RED
+                };
+                yakui::colored_box(color, [1.0, 1.0]);
+            }
+        });
+        ui.finish();
+
+        state.paint(&mut backend, &mut ui);
+        backend.take_commands()
+    };
+    let is_upload =
+        |command: &RecordedCommand| matches!(command, RecordedCommand::BufferUpdate { .. });
+
+    assert!(paint(None).iter().any(is_upload));
+    assert!(!paint(None).iter().any(is_upload));
+    assert!(paint(Some(BOXES / 2)).iter().any(is_upload));
+    assert!(!paint(Some(BOXES / 2)).iter().any(is_upload));
+    assert!(paint(Some(BOXES / 2 + 1)).iter().any(is_upload));
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "software-rasterizer")]
+
+mod common;
+
+use yakui_core::geometry::Color;
+
+use yakui_miniquad::renderer::YakuiRenderer;
+use yakui_miniquad::software::SoftwareBackend;
+use yakui_miniquad::{OutputColorSpace, VertexLayout, YakuiMiniquadState};
+
+use common::new_ui;
+
+const COLOR: Color = Color::rgb(200, 100, 50);
+
+fn paint_box(color_space: OutputColorSpace) -> [u8; 4] {
+    paint_box_with_layout(color_space, VertexLayout::Standard)
+}
+
+fn paint_box_with_layout(color_space: OutputColorSpace, vertex_layout: VertexLayout) -> [u8; 4] {
+    let mut ui = new_ui(32.0, 32.0);
+
+    ui.start();
+    yakui::colored_box(COLOR, [32.0, 32.0]);
+    ui.finish();
+
+    let mut backend = SoftwareBackend::new(32, 32);
+    let mut state = YakuiMiniquadState::with_renderer(YakuiRenderer::with_vertex_layout(
+        &mut backend,
+        vertex_layout,
+    ));
+    state.set_output_color_space(color_space);
+    backend.clear([0.0, 0.0, 0.0, 1.0]);
+    state.paint(&mut backend, &mut ui);
+
+    backend.image().pixel(16, 16)
+}
+
+#[test]
+fn srgb_output_reproduces_srgb_colors() {
+    let [r, g, b, a] = paint_box(OutputColorSpace::Srgb);
+
+    for (painted, expected) in [(r, COLOR.r), (g, COLOR.g), (b, COLOR.b)] {
+        assert!(
+            painted.abs_diff(expected) <= 1,
+            "painted {:?} for {:?}",
+            [r, g, b, a],
+            COLOR
+        );
+    }
+}
+
+#[test]
+fn linear_output_writes_linear_colors() {
+    let [r, g, b, _] = paint_box(OutputColorSpace::Linear);
+    let linear = COLOR.to_linear() * 255.0;
+
+    assert_eq!(
+        [r, g, b],
+        [linear.x, linear.y, linear.z].map(|c| c.round() as u8)
+    );
+}
+
+#[test]
+fn compact_vertices_paint_the_same_colors() {
+    for color_space in [OutputColorSpace::Srgb, OutputColorSpace::Linear] {
+        let standard = paint_box_with_layout(color_space, VertexLayout::Standard);
+        let compact = paint_box_with_layout(color_space, VertexLayout::Compact);
+
+        for (standard, compact) in standard.into_iter().zip(compact) {
+            assert!(
+                standard.abs_diff(compact) <= 1,
+                "{standard:?} and {compact:?} in {color_space:?}"
+            );
+        }
+    }
+}
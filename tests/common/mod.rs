@@ -0,0 +1,152 @@
+//!
+//! Helpers shared by the integration tests, each of which only uses some of them.
+//!
+
+#![allow(dead_code)]
+
+pub mod png;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use yakui_core::event::{EventInterest, EventResponse, WidgetEvent};
+use yakui_core::geometry::{Rect, Vec2};
+use yakui_core::input::{KeyCode, Modifiers, MouseButton};
+use yakui_core::widget::{EventContext, Widget};
+use yakui_core::Yakui;
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::coordinates::CoordinateSpace;
+use yakui_miniquad::YakuiMiniQuad;
+
+/// Creates a yakui context laying the ui out over a whole surface of the given size, at a scale factor of 1.
+pub fn new_ui(width: f32, height: f32) -> Yakui {
+    let mut ui = Yakui::new();
+    ui.set_surface_size(Vec2::new(width, height));
+    ui.set_unscaled_viewport(Rect::from_pos_size(Vec2::ZERO, Vec2::new(width, height)));
+    ui
+}
+
+/// Counts the commands which match.
+pub fn count(commands: &[RecordedCommand], matches: impl Fn(&RecordedCommand) -> bool) -> usize {
+    commands.iter().filter(|command| matches(command)).count()
+}
+
+/// Returns the position of every vertex drawn, in the order the indices of the draw calls refer to them.
+///
+/// Vertices are read from the buffers as they are now, so call this right after painting a single frame.
+pub fn drawn_positions(backend: &RecordingBackend, vertex_size: usize) -> Vec<Vec2> {
+    let mut bindings = None;
+    let mut positions = Vec::new();
+
+    for command in backend.commands() {
+        match command {
+            RecordedCommand::ApplyBindings {
+                vertex_buffers,
+                index_buffer,
+                ..
+            } => bindings = Some((vertex_buffers[0], *index_buffer)),
+            RecordedCommand::Draw {
+                base_element,
+                num_elements,
+                ..
+            } => {
+                let (vertex_buffer, index_buffer) = bindings.expect("draw without bindings");
+                let vertices = backend.buffer_data(vertex_buffer);
+                let indices = backend.buffer_data(index_buffer);
+
+                let elements = *base_element as usize..(*base_element + *num_elements) as usize;
+                for element in elements {
+                    let index =
+                        u16::from_ne_bytes([indices[element * 2], indices[element * 2 + 1]]);
+                    let vertex = &vertices[index as usize * vertex_size..];
+                    let x = f32::from_ne_bytes(vertex[0..4].try_into().unwrap());
+                    let y = f32::from_ne_bytes(vertex[4..8].try_into().unwrap());
+                    positions.push(Vec2::new(x, y));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    positions
+}
+
+/// Returns the position of every vertex yakui painted, in the order the indices of its calls refer to them.
+pub fn painted_positions(ui: &mut Yakui) -> Vec<Vec2> {
+    ui.paint()
+        .layers()
+        .iter()
+        .flat_map(|layer| &layer.calls)
+        .flat_map(|call| {
+            call.indices
+                .iter()
+                .map(|&index| call.vertices[index as usize].position)
+        })
+        .collect()
+}
+
+/// Creates an instance driven without a window, in a window of the given physical size and scale factor.
+pub fn new_instance(width: f32, height: f32, scale_factor: f32) -> YakuiMiniQuad<RecordingBackend> {
+    let mut instance = YakuiMiniQuad::new(&mut RecordingBackend::new());
+    instance.set_window_space(Some(CoordinateSpace::new(
+        Vec2::new(width, height),
+        scale_factor,
+    )));
+    instance
+}
+
+/// The keys a [`KeyRecorder`] got, along with whether they were pressed and the modifiers held.
+pub type RecordedKeys = Rc<RefCell<Vec<(KeyCode, bool, Modifiers)>>>;
+
+/// Shows a [`KeyRecorder`] recording into `keys`.
+pub fn key_recorder(keys: &RecordedKeys) {
+    yakui_core::context::dom().do_widget::<KeyRecorder>(keys.clone());
+}
+
+/// A widget taking keyboard input once clicked, like a text box, which records every key it gets.
+#[derive(Debug, Default)]
+pub struct KeyRecorder {
+    keys: RecordedKeys,
+}
+
+impl Widget for KeyRecorder {
+    type Props<'a> = RecordedKeys;
+    type Response = ();
+
+    fn new() -> Self {
+        KeyRecorder::default()
+    }
+
+    fn update(&mut self, props: Self::Props<'_>) -> Self::Response {
+        self.keys = props;
+    }
+
+    fn event_interest(&self) -> EventInterest {
+        EventInterest::MOUSE_INSIDE | EventInterest::FOCUSED_KEYBOARD
+    }
+
+    fn event(&mut self, ctx: EventContext<'_>, event: &WidgetEvent) -> EventResponse {
+        match event {
+            WidgetEvent::MouseButtonChanged {
+                button: MouseButton::One,
+                down: true,
+                inside: true,
+                ..
+            } => {
+                ctx.input.set_selection(Some(ctx.dom.current()));
+                EventResponse::Sink
+            }
+            WidgetEvent::KeyChanged {
+                key,
+                down,
+                modifiers,
+                ..
+            } => {
+                self.keys.borrow_mut().push((*key, *down, *modifiers));
+                EventResponse::Sink
+            }
+            _ => EventResponse::Bubble,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//!
+//! A minimal PNG codec for the reference images of the golden tests, which only reads back what it writes itself:
+//! 8-bit RGBA, unfiltered, in stored deflate blocks.
+//!
+
+/// Encodes `width` by `height` pixels of 8-bit RGBA as an uncompressed PNG, suitable for checking in as a reference image.
+pub fn encode(width: u32, height: u32, data: &[u8]) -> Vec<u8> {
+    let row_size = width as usize * 4;
+
+    // every scanline is prefixed with its filter type, which is always none here
+    let mut scanlines = Vec::with_capacity((row_size + 1) * height as usize);
+    for row in data.chunks_exact(row_size.max(1)).take(height as usize) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    // zlib stream made up of stored deflate blocks
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = scanlines.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let length = block.len() as u16;
+        zlib.push(is_final as u8);
+        zlib.extend_from_slice(&length.to_le_bytes());
+        zlib.extend_from_slice(&(!length).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bits per channel, rgba, no interlacing
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    write_png_chunk(&mut png, b"IHDR", &header);
+    write_png_chunk(&mut png, b"IDAT", &zlib);
+    write_png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Decodes a PNG written by [`encode`] into its width, height and 8-bit RGBA pixels.
+///
+/// Only 8-bit RGBA images without interlacing, filtering or compression are supported, returns `None` for anything
+/// else, or if the PNG is corrupted. A reference image re-saved by an image editor or optimizer has to be written again
+/// with `YAKUI_MINIQUAD_BLESS=1` before it can be compared against.
+pub fn decode(png: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let mut chunks = png.strip_prefix(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a])?;
+
+    let mut header = None;
+    let mut zlib = Vec::new();
+    loop {
+        let length = u32::from_be_bytes(chunks.get(..4)?.try_into().ok()?) as usize;
+        let chunk = chunks.get(4..8 + length)?;
+        let crc = u32::from_be_bytes(chunks.get(8 + length..12 + length)?.try_into().ok()?);
+        if crc32(chunk) != crc {
+            return None;
+        }
+        chunks = &chunks[12 + length..];
+
+        let (kind, data) = chunk.split_at(4);
+        match kind {
+            b"IHDR" => header = Some(data),
+            b"IDAT" => zlib.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header?;
+    let width = u32::from_be_bytes(header.get(..4)?.try_into().ok()?);
+    let height = u32::from_be_bytes(header.get(4..8)?.try_into().ok()?);
+    if header.get(8..13)? != [8, 6, 0, 0, 0] {
+        return None;
+    }
+
+    // zlib stream made up of stored deflate blocks, which each start on a byte boundary
+    let (zlib_header, mut blocks) = zlib.split_at_checked(2)?;
+    if zlib_header[0] & 0x0f != 8 || u16::from_be_bytes([zlib_header[0], zlib_header[1]]) % 31 != 0
+    {
+        return None;
+    }
+    let mut scanlines = Vec::new();
+    loop {
+        let (&block_header, rest) = blocks.split_first()?;
+        if block_header & 0b110 != 0 {
+            return None;
+        }
+        let length = u16::from_le_bytes(rest.get(..2)?.try_into().ok()?);
+        let inverted_length = u16::from_le_bytes(rest.get(2..4)?.try_into().ok()?);
+        if length != !inverted_length {
+            return None;
+        }
+        scanlines.extend_from_slice(rest.get(4..4 + length as usize)?);
+        blocks = &rest[4 + length as usize..];
+
+        if block_header & 1 != 0 {
+            break;
+        }
+    }
+    if u32::from_be_bytes(blocks.get(..4)?.try_into().ok()?) != adler32(&scanlines) {
+        return None;
+    }
+
+    // every scanline is prefixed with its filter type, which has to be none
+    let row_size = width as usize * 4;
+    if scanlines.len() != (row_size + 1) * height as usize {
+        return None;
+    }
+    let mut data = Vec::with_capacity(row_size * height as usize);
+    for scanline in scanlines.chunks_exact(row_size + 1) {
+        let (&filter, row) = scanline.split_first()?;
+        if filter != 0 {
+            return None;
+        }
+        data.extend_from_slice(row);
+    }
+
+    Some((width, height, data))
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
@@ -0,0 +1,188 @@
+use miniquad::MouseButton;
+use yakui::widgets::Pad;
+use yakui_core::geometry::{Constraints, Rect, UVec2, Vec2};
+use yakui_core::Yakui;
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::coordinates::{clip_to_scissor, CoordinateSpace};
+use yakui_miniquad::input::ConsumedEvent;
+use yakui_miniquad::{YakuiMiniQuad, YakuiMiniquadState};
+
+const SCALE_FACTORS: [f32; 3] = [1.0, 1.5, 2.0];
+
+/// The logical size of the surface every test lays out in.
+const LOGICAL_SIZE: Vec2 = Vec2::new(200.0, 100.0);
+
+fn space(scale_factor: f32) -> CoordinateSpace {
+    CoordinateSpace::new(LOGICAL_SIZE * scale_factor, scale_factor)
+}
+
+/// Lays out a 40x40 widget wanting the mouse at 20,20, and a 80x40 clipping widget at 100,20, in logical pixels.
+fn layout() {
+    yakui::row(|| {
+        yakui::pad(Pad::all(20.0), || {
+            yakui::constrained(Constraints::tight(Vec2::new(40.0, 40.0)), || {
+                yakui::opaque(|| {});
+            });
+        });
+        yakui::pad(Pad::all(20.0), || {
+            yakui::constrained(Constraints::tight(Vec2::new(80.0, 40.0)), || {
+                yakui::scroll_vertical(|| {
+                    yakui::text(16.0, "clipped");
+                });
+            });
+        });
+    });
+}
+
+fn frame(ui: &mut Yakui) {
+    ui.start();
+    layout();
+    ui.finish();
+}
+
+/// Clicks at a position in physical pixels of the window, like miniquad reports it, returning whether yakui sunk the
+/// click.
+fn click(yakui_mq: &mut YakuiMiniQuad<RecordingBackend>, physical: Vec2) -> bool {
+    yakui_mq.mouse_motion_event(physical.x, physical.y);
+    yakui_mq.mouse_button_down_event(MouseButton::Left, physical.x, physical.y);
+    yakui_mq.mouse_button_up_event(MouseButton::Left, physical.x, physical.y);
+
+    yakui_mq.start();
+    layout();
+    yakui_mq.finish().consumed(ConsumedEvent::MouseButton {
+        button: MouseButton::Left,
+        down: true,
+    })
+}
+
+#[test]
+fn logical_and_physical_positions_round_trip() {
+    for scale_factor in SCALE_FACTORS {
+        let space = space(scale_factor);
+        let logical = Vec2::new(12.0, 34.0);
+
+        assert_eq!(space.logical_size(), LOGICAL_SIZE);
+        assert_eq!(space.to_physical(logical), logical * scale_factor);
+        assert_eq!(space.to_logical(space.to_physical(logical)), logical);
+    }
+}
+
+#[test]
+fn clicks_land_on_widgets_at_every_scale() {
+    for scale_factor in SCALE_FACTORS {
+        let mut yakui_mq = YakuiMiniQuad::new(&mut RecordingBackend::new());
+        yakui_mq.set_window_space(Some(space(scale_factor)));
+        yakui_mq.start();
+        layout();
+        yakui_mq.finish();
+
+        // the bottom-right corner of the widget, and just past it
+        let inside = space(scale_factor).to_physical(Vec2::new(55.0, 55.0));
+        let outside = space(scale_factor).to_physical(Vec2::new(65.0, 65.0));
+
+        assert!(
+            click(&mut yakui_mq, inside),
+            "click inside at {scale_factor}"
+        );
+        assert!(
+            !click(&mut yakui_mq, outside),
+            "click outside at {scale_factor}"
+        );
+    }
+}
+
+#[test]
+fn scissor_rects_are_flipped_and_scaled() {
+    for scale_factor in SCALE_FACTORS {
+        let space = space(scale_factor);
+        let clip = Rect::from_pos_size(
+            space.to_physical(Vec2::new(10.0, 20.0)),
+            space.to_physical(Vec2::new(30.0, 40.0)),
+        );
+
+        let s = |logical: f32| (logical * scale_factor).round() as i32;
+        assert_eq!(
+            clip_to_scissor(clip, space.physical_size, space.physical_size.as_uvec2()),
+            Some((s(10.0), s(100.0) - s(60.0), s(30.0), s(40.0))),
+            "scissor at {scale_factor}"
+        );
+    }
+}
+
+#[test]
+fn scissor_rects_are_clamped_to_the_target() {
+    let clip = Rect::from_pos_size(Vec2::new(150.0, -10.0), Vec2::new(100.0, 30.0));
+    let target = UVec2::new(200, 100);
+
+    assert_eq!(
+        clip_to_scissor(clip, target.as_vec2(), target),
+        Some((150, 80, 50, 20))
+    );
+
+    let offscreen = Rect::from_pos_size(Vec2::new(250.0, 0.0), Vec2::new(10.0, 10.0));
+    assert_eq!(clip_to_scissor(offscreen, target.as_vec2(), target), None);
+}
+
+#[test]
+fn painted_scissor_rects_match_clipping_widgets_at_every_scale() {
+    for scale_factor in SCALE_FACTORS {
+        let space = space(scale_factor);
+        let mut ui = Yakui::new();
+        space.apply(&mut ui);
+        frame(&mut ui);
+
+        let mut backend = RecordingBackend::new();
+        let mut state = YakuiMiniquadState::new(&mut backend);
+        backend.take_commands();
+        state.paint(&mut backend, &mut ui);
+
+        let s = |logical: f32| (logical * scale_factor).round() as i32;
+        let expected = RecordedCommand::ApplyScissorRect {
+            x: s(100.0),
+            y: s(100.0) - s(60.0),
+            w: s(80.0),
+            h: s(40.0),
+        };
+
+        assert!(
+            backend.commands().contains(&expected),
+            "expected {expected:?} at {scale_factor} in {:?}",
+            backend.commands()
+        );
+    }
+}
+
+#[test]
+fn painted_scissor_rects_are_confined_to_the_viewport() {
+    for scale_factor in SCALE_FACTORS {
+        let space = space(scale_factor);
+        let viewport = Rect::from_pos_size(
+            space.to_physical(Vec2::new(40.0, 10.0)),
+            space.to_physical(Vec2::new(160.0, 90.0)),
+        );
+        let mut ui = Yakui::new();
+        space.apply_to_viewport(&mut ui, viewport);
+        frame(&mut ui);
+
+        let mut backend = RecordingBackend::new();
+        let mut state = YakuiMiniquadState::new(&mut backend);
+        backend.take_commands();
+        state.paint(&mut backend, &mut ui);
+
+        // the clipping widget is offset by the viewport, and cut off by its right edge
+        let s = |logical: f32| (logical * scale_factor).round() as i32;
+        let expected = RecordedCommand::ApplyScissorRect {
+            x: s(140.0),
+            y: s(100.0) - s(70.0),
+            w: s(200.0) - s(140.0),
+            h: s(70.0) - s(30.0),
+        };
+
+        assert!(
+            backend.commands().contains(&expected),
+            "expected {expected:?} at {scale_factor} in {:?}",
+            backend.commands()
+        );
+    }
+}
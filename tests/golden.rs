@@ -0,0 +1,114 @@
+#![cfg(feature = "software-rasterizer")]
+
+//! Renders a small menu with the software rasterizer and compares it against `tests/golden/menu.png`.
+//!
+//! Run with `YAKUI_MINIQUAD_BLESS=1` to write the reference image again after an intended change to the output.
+//! The reference image is read with the minimal codec in `common::png`, which only reads the uncompressed PNGs it writes
+//! itself, so bless it again rather than saving it with an image editor or optimizer.
+
+mod common;
+
+use std::path::Path;
+
+use yakui_core::geometry::{Color, Constraints, UVec2, Vec2};
+use yakui_core::paint::{Texture, TextureFormat};
+
+use yakui_miniquad::software::{RgbaImage, SoftwareBackend};
+use yakui_miniquad::YakuiMiniquadState;
+
+use common::{new_ui, png};
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+
+/// Channels are allowed to be off by this much, to not fail on rounding differences between platforms.
+const TOLERANCE: u8 = 2;
+
+fn checkerboard() -> Texture {
+    let mut data = Vec::with_capacity(4 * 4 * 4);
+    for y in 0..4 {
+        for x in 0..4 {
+            if (x + y) % 2 == 0 {
+                data.extend_from_slice(&[255, 200, 0, 255]);
+            } else {
+                data.extend_from_slice(&[40, 40, 160, 255]);
+            }
+        }
+    }
+    Texture::new(TextureFormat::Rgba8Srgb, UVec2::new(4, 4), data)
+}
+
+fn render_menu() -> RgbaImage {
+    let mut ui = new_ui(WIDTH as f32, HEIGHT as f32);
+    let icon = ui.add_texture(checkerboard());
+
+    // an icon drawn from a texture, text blended over the background, and entries clipped by a scroll area
+    ui.start();
+    yakui::column(|| {
+        yakui::row(|| {
+            yakui::image(icon, [32.0, 32.0]);
+            yakui::text(20.0, "Menu");
+        });
+        yakui::constrained(Constraints::tight(Vec2::new(120.0, 50.0)), || {
+            yakui::scroll_vertical(|| {
+                yakui::column(|| {
+                    for color in [Color::RED, Color::GREEN, Color::BLUE] {
+                        yakui::colored_box(color, [120.0, 20.0]);
+                    }
+                });
+            });
+        });
+    });
+    ui.finish();
+
+    let mut backend = SoftwareBackend::new(WIDTH, HEIGHT);
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.clear([0.1, 0.1, 0.1, 1.0]);
+    state.paint(&mut backend, &mut ui);
+
+    backend.image().clone()
+}
+
+#[test]
+fn menu_matches_reference_image() {
+    let image = render_menu();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/menu.png");
+
+    if std::env::var_os("YAKUI_MINIQUAD_BLESS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            png::encode(image.width(), image.height(), image.as_rgba8()),
+        )
+        .unwrap();
+    }
+
+    let file =
+        std::fs::read(&path).expect("missing reference image, run with YAKUI_MINIQUAD_BLESS=1");
+    let (width, height, data) = png::decode(&file).expect(
+        "reference image is not a png written by png::encode, run with YAKUI_MINIQUAD_BLESS=1",
+    );
+    let reference = RgbaImage::from_rgba8(width, height, data);
+
+    let differing = image.count_differing_pixels(&reference, TOLERANCE);
+    assert_eq!(
+        differing, 0,
+        "{differing} pixels differ from the reference image"
+    );
+}
+
+#[test]
+fn png_round_trips() {
+    let image = render_menu();
+
+    let encoded = png::encode(image.width(), image.height(), image.as_rgba8());
+    assert_eq!(
+        png::decode(&encoded),
+        Some((image.width(), image.height(), image.as_rgba8().to_vec()))
+    );
+
+    let mut corrupted = encoded;
+    let last_pixel = corrupted.len() - 12 - 4 - 4 - 5;
+    corrupted[last_pixel] ^= 1;
+    assert_eq!(png::decode(&corrupted), None);
+}
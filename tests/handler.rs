@@ -0,0 +1,262 @@
+mod common;
+
+use miniquad::{EventHandler, KeyCode, KeyMods, MouseButton, PassAction, TouchPhase};
+use yakui_core::geometry::{Constraints, Vec2};
+
+use yakui_miniquad::backend::{GraphicsBackend, RecordedCommand, RecordingBackend};
+use yakui_miniquad::coordinates::CoordinateSpace;
+use yakui_miniquad::handler::{YakuiEventHandler, YakuiHandler};
+
+use common::{key_recorder, RecordedKeys};
+
+/// An event which reached the [`Game`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Logged {
+    Update,
+    Draw,
+    MouseMotion,
+    MouseButton(MouseButton, bool),
+    Key(KeyCode, bool),
+    Touch(u64, TouchPhase),
+}
+
+/// A game logging the events it gets, drawing the ui a 100x100 key recorder in the top-left corner and a 100x100
+/// button to the right of it.
+#[derive(Default)]
+struct Game {
+    ctx: RecordingBackend,
+    keys: RecordedKeys,
+    log: Vec<Logged>,
+}
+
+impl EventHandler for Game {
+    fn update(&mut self) {
+        self.log.push(Logged::Update);
+
+        // yakui is only bound to the thread between start and finish
+        yakui::row(|| {
+            yakui::constrained(Constraints::tight(Vec2::splat(100.0)), || {
+                key_recorder(&self.keys);
+            });
+            yakui::constrained(Constraints::tight(Vec2::splat(100.0)), || {
+                yakui::button("button");
+            });
+        });
+    }
+
+    fn draw(&mut self) {
+        self.log.push(Logged::Draw);
+        self.ctx
+            .begin_pass(None, PassAction::clear_color(0.0, 0.0, 0.0, 1.0));
+        self.ctx.end_render_pass();
+    }
+
+    fn mouse_motion_event(&mut self, _x: f32, _y: f32) {
+        self.log.push(Logged::MouseMotion);
+    }
+
+    fn mouse_button_down_event(&mut self, button: MouseButton, _x: f32, _y: f32) {
+        self.log.push(Logged::MouseButton(button, true));
+    }
+
+    fn mouse_button_up_event(&mut self, button: MouseButton, _x: f32, _y: f32) {
+        self.log.push(Logged::MouseButton(button, false));
+    }
+
+    fn key_down_event(&mut self, keycode: KeyCode, _keymods: KeyMods, _repeat: bool) {
+        self.log.push(Logged::Key(keycode, true));
+    }
+
+    fn key_up_event(&mut self, keycode: KeyCode, _keymods: KeyMods) {
+        self.log.push(Logged::Key(keycode, false));
+    }
+
+    fn touch_event(&mut self, phase: TouchPhase, id: u64, _x: f32, _y: f32) {
+        self.log.push(Logged::Touch(id, phase));
+    }
+}
+
+impl YakuiHandler<RecordingBackend> for Game {
+    fn context(&mut self) -> &mut RecordingBackend {
+        &mut self.ctx
+    }
+}
+
+/// Wraps a [`Game`] in a 640x480 window, which has drawn its first frame.
+fn new_handler() -> YakuiEventHandler<Game, RecordingBackend> {
+    let mut handler = YakuiEventHandler::new(Game::default());
+    handler
+        .yakui()
+        .set_window_space(Some(CoordinateSpace::new(Vec2::new(640.0, 480.0), 1.0)));
+    handler.update();
+    handler.handler().log.clear();
+    handler
+}
+
+/// Returns what the game logged since this was last called.
+fn take_log(handler: &mut YakuiEventHandler<Game, RecordingBackend>) -> Vec<Logged> {
+    std::mem::take(&mut handler.handler().log)
+}
+
+fn click(handler: &mut YakuiEventHandler<Game, RecordingBackend>, x: f32, y: f32) {
+    handler.mouse_motion_event(x, y);
+    handler.mouse_button_down_event(MouseButton::Left, x, y);
+    handler.mouse_button_up_event(MouseButton::Left, x, y);
+}
+
+#[test]
+fn the_ui_is_built_during_the_update_and_drawn_after_the_game() {
+    let mut handler = new_handler();
+
+    handler.update();
+    assert_eq!(take_log(&mut handler), [Logged::Update]);
+
+    handler.handler().ctx.take_commands();
+    handler.draw();
+    assert_eq!(take_log(&mut handler), [Logged::Draw]);
+
+    // the game's own pass comes first, then the ui laid out by finish is drawn on top without clearing it
+    let commands = handler.handler().ctx.take_commands();
+    assert_eq!(
+        commands[..3],
+        [
+            RecordedCommand::BeginPass {
+                pass: None,
+                clear_color: Some((0.0, 0.0, 0.0, 1.0)),
+            },
+            RecordedCommand::EndRenderPass,
+            RecordedCommand::BeginPass {
+                pass: None,
+                clear_color: None,
+            },
+        ]
+    );
+    assert!(commands
+        .iter()
+        .any(|command| matches!(command, RecordedCommand::Draw { .. })));
+    assert_eq!(
+        commands[commands.len() - 2..],
+        [RecordedCommand::EndRenderPass, RecordedCommand::CommitFrame]
+    );
+}
+
+#[test]
+fn only_presses_yakui_didnt_sink_and_their_releases_are_forwarded() {
+    let mut handler = new_handler();
+
+    // the button sinks the click, but the game still follows the cursor
+    click(&mut handler, 150.0, 50.0);
+    assert_eq!(take_log(&mut handler), [Logged::MouseMotion]);
+
+    click(&mut handler, 300.0, 300.0);
+    assert_eq!(
+        take_log(&mut handler),
+        [
+            Logged::MouseMotion,
+            Logged::MouseButton(MouseButton::Left, true),
+            Logged::MouseButton(MouseButton::Left, false),
+        ]
+    );
+
+    // a press the game got is released over the ui
+    handler.mouse_button_down_event(MouseButton::Right, 300.0, 300.0);
+    handler.mouse_motion_event(150.0, 50.0);
+    handler.mouse_button_up_event(MouseButton::Right, 150.0, 50.0);
+    assert_eq!(
+        take_log(&mut handler),
+        [
+            Logged::MouseButton(MouseButton::Right, true),
+            Logged::MouseMotion,
+            Logged::MouseButton(MouseButton::Right, false),
+        ]
+    );
+
+    // a press the button sunk is released outside of the ui
+    handler.mouse_button_down_event(MouseButton::Left, 150.0, 50.0);
+    handler.mouse_motion_event(300.0, 300.0);
+    handler.mouse_button_up_event(MouseButton::Left, 300.0, 300.0);
+    assert_eq!(take_log(&mut handler), [Logged::MouseMotion]);
+}
+
+#[test]
+fn keys_are_forwarded_unless_a_focused_widget_sinks_them() {
+    let mut handler = new_handler();
+
+    handler.key_down_event(KeyCode::A, KeyMods::default(), false);
+    handler.key_up_event(KeyCode::A, KeyMods::default());
+    assert_eq!(
+        take_log(&mut handler),
+        [
+            Logged::Key(KeyCode::A, true),
+            Logged::Key(KeyCode::A, false)
+        ]
+    );
+
+    // the key recorder takes the keyboard once clicked
+    click(&mut handler, 50.0, 50.0);
+    take_log(&mut handler);
+    handler.key_down_event(KeyCode::B, KeyMods::default(), false);
+    assert_eq!(take_log(&mut handler), []);
+
+    // clicking outside of the ui unfocuses it, but the release of a key it got still isn't forwarded
+    click(&mut handler, 300.0, 300.0);
+    take_log(&mut handler);
+    handler.key_up_event(KeyCode::B, KeyMods::default());
+    assert_eq!(take_log(&mut handler), []);
+    assert_eq!(handler.handler().keys.borrow().len(), 1);
+}
+
+#[test]
+fn touches_are_forwarded_if_they_start_outside_of_the_ui() {
+    let mut handler = new_handler();
+
+    for (id, x, y) in [(1, 150.0, 50.0), (2, 300.0, 300.0)] {
+        handler.touch_event(TouchPhase::Started, id, x, y);
+        handler.touch_event(TouchPhase::Moved, id, x + 10.0, y);
+        handler.touch_event(TouchPhase::Ended, id, x + 10.0, y);
+    }
+
+    assert_eq!(
+        take_log(&mut handler),
+        [
+            Logged::Touch(2, TouchPhase::Started),
+            Logged::Touch(2, TouchPhase::Moved),
+            Logged::Touch(2, TouchPhase::Ended),
+        ]
+    );
+}
+
+#[test]
+fn events_yakui_never_sees_are_forwarded_even_after_a_sunk_one() {
+    let mut handler = new_handler();
+
+    // the key recorder takes the keyboard and sinks a key
+    click(&mut handler, 50.0, 50.0);
+    take_log(&mut handler);
+    handler.key_down_event(KeyCode::B, KeyMods::default(), false);
+    assert!(handler.yakui().has_keyboard_focus());
+
+    // yakui has no key for this one, so it can't have sunk it
+    handler.key_down_event(KeyCode::World1, KeyMods::default(), false);
+    handler.key_up_event(KeyCode::World1, KeyMods::default());
+    assert_eq!(
+        take_log(&mut handler),
+        [
+            Logged::Key(KeyCode::World1, true),
+            Logged::Key(KeyCode::World1, false),
+        ]
+    );
+}
+
+#[test]
+fn quitting_forgets_forwarded_presses() {
+    let mut handler = new_handler();
+
+    handler.key_down_event(KeyCode::A, KeyMods::default(), false);
+    handler.quit_requested_event();
+    take_log(&mut handler);
+
+    // the release of a press from before quitting was requested isn't forwarded on its own
+    handler.key_up_event(KeyCode::A, KeyMods::default());
+    assert_eq!(take_log(&mut handler), []);
+}
@@ -0,0 +1,519 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use miniquad::{KeyCode, KeyMods, MouseButton, TouchPhase};
+use yakui::widgets::Pad;
+use yakui_core::event::{Event, EventInterest, EventResponse, WidgetEvent};
+use yakui_core::geometry::{Constraints, Rect, Vec2};
+use yakui_core::input::{KeyCode as YakuiKeyCode, Modifiers, MouseButton as YakuiMouseButton};
+use yakui_core::widget::{EventContext, Widget};
+
+use yakui_miniquad::backend::RecordingBackend;
+use yakui_miniquad::input::ConsumedEvent;
+use yakui_miniquad::YakuiMiniQuad;
+
+use common::{key_recorder, new_instance, RecordedKeys};
+
+/// Builds a frame with a 100x100 key recorder in the top-left corner, and a 100x100 button to the right of it.
+///
+/// Returns whether the button was clicked since the previous frame.
+fn frame(yakui_mq: &mut YakuiMiniQuad<RecordingBackend>, keys: &RecordedKeys) -> bool {
+    let mut clicked = false;
+    yakui_mq.start();
+    yakui::row(|| {
+        yakui::constrained(Constraints::tight(Vec2::splat(100.0)), || {
+            key_recorder(keys);
+        });
+        yakui::constrained(Constraints::tight(Vec2::splat(100.0)), || {
+            clicked = yakui::button("button").clicked;
+        });
+    });
+    yakui_mq.finish();
+    clicked
+}
+
+/// Builds a frame with a 200x100 scroll area in the top-left corner, filled by a button three times as high.
+///
+/// Returns whether the button was clicked since the previous frame.
+fn scroll_frame(yakui_mq: &mut YakuiMiniQuad<RecordingBackend>) -> bool {
+    let mut clicked = false;
+    yakui_mq.start();
+    yakui::constrained(Constraints::tight(Vec2::new(200.0, 100.0)), || {
+        yakui::scroll_vertical(|| {
+            yakui::constrained(Constraints::tight(Vec2::new(200.0, 300.0)), || {
+                clicked = yakui::button("button").clicked;
+            });
+        });
+    });
+    yakui_mq.finish();
+    clicked
+}
+
+/// Clicks the key recorder, so it gets the keyboard.
+fn focus_key_recorder(yakui_mq: &mut YakuiMiniQuad<RecordingBackend>) {
+    yakui_mq.mouse_motion_event(50.0, 50.0);
+    yakui_mq.mouse_button_down_event(MouseButton::Left, 50.0, 50.0);
+    yakui_mq.mouse_button_up_event(MouseButton::Left, 50.0, 50.0);
+}
+
+fn shift(shift: bool) -> KeyMods {
+    KeyMods {
+        shift,
+        ..Default::default()
+    }
+}
+
+/// Builds a frame with a widget wanting the mouse, which covers as much of the window as it can.
+fn cover_window(yakui_mq: &mut YakuiMiniQuad<RecordingBackend>) {
+    yakui_mq.start();
+    yakui::constrained(Constraints::tight(Vec2::splat(10_000.0)), || {
+        yakui::opaque(|| {});
+    });
+    yakui_mq.finish();
+}
+
+#[test]
+fn input_is_handled_without_a_window() {
+    // nothing asks miniquad for the window before the first frame
+    let mut yakui_mq = YakuiMiniQuad::new(&mut RecordingBackend::new());
+    yakui_mq.mouse_motion_event(10.0, 10.0);
+    yakui_mq.resize_event(640.0, 480.0);
+    yakui_mq.mouse_motion_event(700.0, 10.0);
+
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    cover_window(&mut yakui_mq);
+    yakui_mq.mouse_motion_event(600.0, 10.0);
+    assert!(yakui_mq.is_cursor_over_ui());
+    yakui_mq.mouse_motion_event(700.0, 10.0);
+    assert!(!yakui_mq.is_cursor_over_ui());
+
+    // resizing the window resizes the space it was given
+    yakui_mq.resize_event(800.0, 600.0);
+    cover_window(&mut yakui_mq);
+    yakui_mq.mouse_motion_event(700.0, 10.0);
+    assert!(yakui_mq.is_cursor_over_ui());
+}
+
+/// An event an [`EventRecorder`] got.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Seen {
+    Moved(Vec2),
+    Scrolled(Vec2),
+    Button(YakuiMouseButton, bool),
+    Key(YakuiKeyCode, bool),
+    Text(char),
+}
+
+/// A widget which takes the keyboard once clicked and records every event it gets.
+#[derive(Debug, Default)]
+struct EventRecorder {
+    seen: Rc<RefCell<Vec<Seen>>>,
+}
+
+impl Widget for EventRecorder {
+    type Props<'a> = Rc<RefCell<Vec<Seen>>>;
+    type Response = ();
+
+    fn new() -> Self {
+        EventRecorder::default()
+    }
+
+    fn update(&mut self, props: Self::Props<'_>) -> Self::Response {
+        self.seen = props;
+    }
+
+    fn event_interest(&self) -> EventInterest {
+        EventInterest::MOUSE_ALL | EventInterest::FOCUSED_KEYBOARD
+    }
+
+    fn event(&mut self, ctx: EventContext<'_>, event: &WidgetEvent) -> EventResponse {
+        let seen = match *event {
+            WidgetEvent::MouseMoved(Some(position)) => Seen::Moved(position),
+            WidgetEvent::MouseScroll { delta } => Seen::Scrolled(delta),
+            WidgetEvent::MouseButtonChanged { button, down, .. } => {
+                if down {
+                    ctx.input.set_selection(Some(ctx.dom.current()));
+                }
+                Seen::Button(button, down)
+            }
+            WidgetEvent::KeyChanged { key, down, .. } => Seen::Key(key, down),
+            WidgetEvent::TextInput(character) => Seen::Text(character),
+            _ => return EventResponse::Bubble,
+        };
+        self.seen.borrow_mut().push(seen);
+        EventResponse::Sink
+    }
+}
+
+#[test]
+fn every_former_event_handler_method_reaches_yakui() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+
+    // these used to be the methods of YakuiMiniQuad's own EventHandler implementation
+    yakui_mq.resize_event(320.0, 240.0);
+    yakui_mq.start();
+    yakui::constrained(Constraints::tight(Vec2::splat(100.0)), || {
+        yakui_core::context::dom().do_widget::<EventRecorder>(seen.clone());
+    });
+    yakui_mq.finish();
+    assert_eq!(
+        yakui_mq.ctx().layout_dom().unscaled_viewport(),
+        Rect::from_pos_size(Vec2::ZERO, Vec2::new(320.0, 240.0))
+    );
+
+    yakui_mq.mouse_motion_event(50.0, 50.0);
+    yakui_mq.mouse_button_down_event(MouseButton::Left, 50.0, 50.0);
+    yakui_mq.mouse_button_up_event(MouseButton::Left, 50.0, 50.0);
+    yakui_mq.mouse_wheel_event(0.0, 1.0);
+    yakui_mq.key_down_event(KeyCode::A, KeyMods::default(), false);
+    yakui_mq.char_event('a', KeyMods::default(), false);
+    yakui_mq.key_up_event(KeyCode::A, KeyMods::default());
+
+    let seen = seen.borrow();
+    assert_eq!(seen[0], Seen::Moved(Vec2::new(50.0, 50.0)));
+    assert_eq!(
+        seen[1..],
+        [
+            Seen::Button(YakuiMouseButton::One, true),
+            Seen::Button(YakuiMouseButton::One, false),
+            Seen::Scrolled(Vec2::new(0.0, 1.0)),
+            Seen::Key(YakuiKeyCode::KeyA, true),
+            Seen::Text('a'),
+            Seen::Key(YakuiKeyCode::KeyA, false),
+        ]
+    );
+}
+
+#[test]
+fn missed_modifier_releases_are_repaired_by_the_next_key() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+    focus_key_recorder(&mut yakui_mq);
+
+    // some platforms report the modifiers from before the key, so shift itself counts
+    yakui_mq.key_down_event(KeyCode::LeftShift, shift(false), false);
+    yakui_mq.key_down_event(KeyCode::A, shift(true), false);
+
+    // the release of shift went missing, but the next key says it's no longer held
+    yakui_mq.key_down_event(KeyCode::B, shift(false), false);
+
+    assert_eq!(
+        *keys.borrow(),
+        [
+            (YakuiKeyCode::ShiftLeft, true, Modifiers::SHIFT),
+            (YakuiKeyCode::KeyA, true, Modifiers::SHIFT),
+            (YakuiKeyCode::KeyB, true, Modifiers::empty()),
+        ]
+    );
+}
+
+#[test]
+fn unchanged_modifiers_are_not_sent_again() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+    focus_key_recorder(&mut yakui_mq);
+
+    yakui_mq.key_down_event(KeyCode::A, shift(true), false);
+
+    // yakui is told about other modifiers behind our back, which only sticks if shift isn't sent again
+    yakui_mq
+        .ctx()
+        .handle_event(Event::ModifiersChanged(Modifiers::CONTROL));
+    yakui_mq.key_down_event(KeyCode::B, shift(true), false);
+    yakui_mq.char_event('b', shift(true), false);
+    yakui_mq.key_up_event(KeyCode::B, shift(true));
+
+    assert_eq!(
+        *keys.borrow(),
+        [
+            (YakuiKeyCode::KeyA, true, Modifiers::SHIFT),
+            (YakuiKeyCode::KeyB, true, Modifiers::CONTROL),
+            (YakuiKeyCode::KeyB, false, Modifiers::CONTROL),
+        ]
+    );
+}
+
+#[test]
+fn tapping_clicks() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+
+    yakui_mq.touch_event(TouchPhase::Started, 0, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 0, 150.0, 50.0);
+
+    assert!(frame(&mut yakui_mq, &keys));
+    assert_eq!(
+        yakui_mq.input_capture().consumed_events,
+        [
+            ConsumedEvent::Touch {
+                id: 0,
+                phase: TouchPhase::Started,
+            },
+            ConsumedEvent::Touch {
+                id: 0,
+                phase: TouchPhase::Ended,
+            },
+        ]
+    );
+}
+
+#[test]
+fn cancelled_touches_dont_click() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+
+    yakui_mq.touch_event(TouchPhase::Started, 0, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Cancelled, 0, 150.0, 50.0);
+
+    assert!(!frame(&mut yakui_mq, &keys));
+    assert!(!yakui_mq.is_cursor_over_ui());
+}
+
+#[test]
+fn dragging_two_fingers_scrolls_without_clicking() {
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    scroll_frame(&mut yakui_mq);
+
+    yakui_mq.touch_event(TouchPhase::Started, 0, 50.0, 60.0);
+    yakui_mq.touch_event(TouchPhase::Started, 1, 150.0, 60.0);
+    yakui_mq.touch_event(TouchPhase::Moved, 0, 50.0, 40.0);
+    yakui_mq.touch_event(TouchPhase::Moved, 1, 150.0, 40.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 0, 50.0, 40.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 1, 150.0, 40.0);
+
+    assert!(!scroll_frame(&mut yakui_mq));
+    let scrolls = yakui_mq
+        .input_capture()
+        .consumed_events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                ConsumedEvent::Touch {
+                    phase: TouchPhase::Moved,
+                    ..
+                }
+            )
+        })
+        .count();
+    assert_eq!(scrolls, 2, "both fingers moving scrolls");
+}
+
+#[test]
+fn lifting_the_first_finger_leaves_the_second_one_alone() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+
+    // the second finger turns the press into a scroll, which ends once the first finger is lifted
+    yakui_mq.touch_event(TouchPhase::Started, 0, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Started, 1, 180.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 0, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Moved, 1, 150.0, 50.0);
+    assert!(!frame(&mut yakui_mq, &keys));
+    yakui_mq.touch_event(TouchPhase::Ended, 1, 150.0, 50.0);
+    assert!(!frame(&mut yakui_mq, &keys));
+
+    // without two finger scrolling, the second finger is ignored and the first one still clicks
+    yakui_mq.set_two_finger_scroll(false);
+    yakui_mq.touch_event(TouchPhase::Started, 2, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Started, 3, 180.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 2, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Moved, 3, 400.0, 400.0);
+    assert!(frame(&mut yakui_mq, &keys));
+    yakui_mq.touch_event(TouchPhase::Ended, 3, 400.0, 400.0);
+    assert!(!frame(&mut yakui_mq, &keys));
+}
+
+#[test]
+fn cursor_is_over_ui_where_yakui_hit_tests_widgets() {
+    for scale_factor in [1.0, 2.0] {
+        let physical = |logical: Vec2| logical * scale_factor;
+        let mut yakui_mq = new_instance(400.0 * scale_factor, 300.0 * scale_factor, scale_factor);
+        let viewport = Rect::from_pos_size(
+            physical(Vec2::new(40.0, 30.0)),
+            physical(Vec2::new(200.0, 100.0)),
+        );
+        yakui_mq.set_viewport(Some(viewport));
+
+        // a 40x40 widget at 20,20 of the viewport, and a button at 100,20 clipped to 80x40 by a scroll area
+        yakui_mq.start();
+        yakui::row(|| {
+            yakui::pad(Pad::all(20.0), || {
+                yakui::constrained(Constraints::tight(Vec2::splat(40.0)), || {
+                    yakui::opaque(|| {});
+                });
+            });
+            yakui::pad(Pad::all(20.0), || {
+                yakui::constrained(Constraints::tight(Vec2::new(80.0, 40.0)), || {
+                    yakui::scroll_vertical(|| {
+                        yakui::constrained(Constraints::tight(Vec2::new(80.0, 200.0)), || {
+                            let _ = yakui::button("clipped");
+                        });
+                    });
+                });
+            });
+        });
+        yakui_mq.finish();
+
+        for (logical, over_ui) in [
+            (Vec2::new(40.0, 40.0), true),
+            (Vec2::new(10.0, 10.0), false),
+            (Vec2::new(120.0, 40.0), true),
+            (Vec2::new(120.0, 80.0), false),
+            (Vec2::new(-20.0, 40.0), false),
+        ] {
+            let position = viewport.pos() + physical(logical);
+            yakui_mq.mouse_motion_event(position.x, position.y);
+            assert_eq!(
+                yakui_mq.is_cursor_over_ui(),
+                over_ui,
+                "{logical} at scale {scale_factor}"
+            );
+        }
+    }
+}
+
+#[test]
+fn cursor_leaving_the_window_is_no_longer_over_ui() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+
+    yakui_mq.mouse_motion_event(150.0, 50.0);
+    assert!(yakui_mq.is_cursor_over_ui());
+
+    // without a button held, the last motion reported is still inside the window
+    yakui_mq.mouse_left_window();
+    assert!(!yakui_mq.is_cursor_over_ui());
+    assert!(!yakui_mq.has_mouse_focus());
+}
+
+#[test]
+fn clicking_a_widget_taking_keyboard_input_wants_the_keyboard() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    let wants_keyboard = |yakui_mq: &mut YakuiMiniQuad<RecordingBackend>| {
+        frame(yakui_mq, &keys);
+        yakui_mq.input_capture().wants_keyboard
+    };
+    assert!(!wants_keyboard(&mut yakui_mq));
+
+    focus_key_recorder(&mut yakui_mq);
+    assert!(wants_keyboard(&mut yakui_mq));
+    assert!(wants_keyboard(&mut yakui_mq));
+
+    // the button sinks the click without taking the selection away
+    yakui_mq.mouse_motion_event(150.0, 50.0);
+    yakui_mq.mouse_button_down_event(MouseButton::Left, 150.0, 50.0);
+    yakui_mq.mouse_button_up_event(MouseButton::Left, 150.0, 50.0);
+    assert!(wants_keyboard(&mut yakui_mq));
+
+    // clicking outside of the ui does
+    yakui_mq.mouse_motion_event(400.0, 400.0);
+    yakui_mq.mouse_button_down_event(MouseButton::Left, 400.0, 400.0);
+    yakui_mq.mouse_button_up_event(MouseButton::Left, 400.0, 400.0);
+    assert!(!wants_keyboard(&mut yakui_mq));
+
+    yakui_mq.touch_event(TouchPhase::Started, 0, 50.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 0, 50.0, 50.0);
+    assert!(wants_keyboard(&mut yakui_mq));
+
+    yakui_mq.reset_input();
+    assert!(!wants_keyboard(&mut yakui_mq));
+
+    // asking doesn't send the focused widget anything
+    yakui_mq.key_down_event(KeyCode::A, KeyMods::default(), false);
+    assert_eq!(
+        *keys.borrow(),
+        [(YakuiKeyCode::KeyA, true, Modifiers::empty())]
+    );
+}
+
+#[test]
+fn focused_widgets_which_are_gone_dont_want_the_keyboard() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+    focus_key_recorder(&mut yakui_mq);
+    frame(&mut yakui_mq, &keys);
+    assert!(yakui_mq.input_capture().wants_keyboard);
+
+    cover_window(&mut yakui_mq);
+    assert!(!yakui_mq.input_capture().wants_keyboard);
+}
+
+#[test]
+fn resetting_input_releases_everything_without_clicking() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+    focus_key_recorder(&mut yakui_mq);
+
+    // a key and the mouse are held down on the button when the window loses focus
+    yakui_mq.key_down_event(KeyCode::A, shift(true), false);
+    yakui_mq.mouse_motion_event(150.0, 50.0);
+    yakui_mq.mouse_button_down_event(MouseButton::Left, 150.0, 50.0);
+    yakui_mq.window_minimized_event();
+
+    assert!(!frame(&mut yakui_mq, &keys));
+    assert!(!yakui_mq.is_cursor_over_ui());
+    assert!(!yakui_mq.input_capture().wants_pointer);
+    assert_eq!(
+        *keys.borrow(),
+        [
+            (YakuiKeyCode::KeyA, true, Modifiers::SHIFT),
+            (YakuiKeyCode::KeyA, false, Modifiers::SHIFT),
+        ]
+    );
+
+    // the release that finally arrives is no click either
+    yakui_mq.mouse_button_up_event(MouseButton::Left, 150.0, 50.0);
+    assert!(!frame(&mut yakui_mq, &keys));
+
+    // a touch held on the button is forgotten, so its end doesn't click
+    yakui_mq.touch_event(TouchPhase::Started, 0, 150.0, 50.0);
+    yakui_mq.reset_input();
+    yakui_mq.touch_event(TouchPhase::Moved, 0, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 0, 150.0, 50.0);
+    assert!(!frame(&mut yakui_mq, &keys));
+
+    // and the next tap clicks as usual
+    yakui_mq.touch_event(TouchPhase::Started, 1, 150.0, 50.0);
+    yakui_mq.touch_event(TouchPhase::Ended, 1, 150.0, 50.0);
+    assert!(frame(&mut yakui_mq, &keys));
+}
+
+#[test]
+fn releasing_one_of_two_held_modifier_keys_keeps_the_modifier() {
+    let keys = RecordedKeys::default();
+    let mut yakui_mq = new_instance(640.0, 480.0, 1.0);
+    frame(&mut yakui_mq, &keys);
+    focus_key_recorder(&mut yakui_mq);
+
+    yakui_mq.key_down_event(KeyCode::LeftShift, shift(false), false);
+    yakui_mq.key_down_event(KeyCode::RightShift, shift(true), false);
+
+    // some platforms report shift as released along with either key
+    yakui_mq.key_up_event(KeyCode::LeftShift, shift(false));
+    yakui_mq.key_down_event(KeyCode::A, shift(true), false);
+    yakui_mq.key_up_event(KeyCode::RightShift, shift(true));
+
+    assert_eq!(
+        *keys.borrow(),
+        [
+            (YakuiKeyCode::ShiftLeft, true, Modifiers::SHIFT),
+            (YakuiKeyCode::ShiftRight, true, Modifiers::SHIFT),
+            (YakuiKeyCode::ShiftLeft, false, Modifiers::SHIFT),
+            (YakuiKeyCode::KeyA, true, Modifiers::SHIFT),
+            (YakuiKeyCode::ShiftRight, false, Modifiers::empty()),
+        ]
+    );
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use yakui_core::geometry::{Color, Vec2};
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::{VertexLayout, YakuiMiniquadState};
+
+use common::{drawn_positions, new_ui};
+
+const BOXES: usize = 50_000;
+
+#[test]
+fn calls_with_more_vertices_than_16_bit_indices_address_are_split() {
+    let mut ui = new_ui(640.0, 480.0);
+
+    ui.start();
+    yakui::row(|| {
+        for _ in 0..BOXES {
+            yakui::colored_box(Color::RED, [1.0, 1.0]);
+        }
+    });
+    ui.finish();
+
+    // yakui merges every box into a single call, wrapping its indices around
+    let layers = ui.paint().layers();
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].calls.len(), 1);
+
+    let call = &layers[0].calls[0];
+    assert_eq!(call.vertices.len(), BOXES * 4);
+    assert_eq!(call.indices.len(), BOXES * 6);
+
+    // each box has four vertices and six indices, which refer to the vertices of that box
+    let expected: Vec<Vec2> = call
+        .indices
+        .chunks_exact(6)
+        .enumerate()
+        .flat_map(|(box_index, indices)| {
+            let first_vertex = box_index * 4;
+            indices.iter().map(move |&index| {
+                let offset = index.wrapping_sub(first_vertex as u16) as usize;
+                assert!(offset < 4);
+                call.vertices[first_vertex + offset].position
+            })
+        })
+        .collect();
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+
+    let vertex_buffers: Vec<usize> = backend
+        .commands()
+        .iter()
+        .filter_map(|command| match command {
+            RecordedCommand::ApplyBindings { vertex_buffers, .. } => Some(vertex_buffers[0]),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vertex_buffers.len(), 4, "200k vertices need four batches");
+
+    let drawn = drawn_positions(&backend, VertexLayout::Standard.vertex_size());
+    assert_eq!(drawn.len(), expected.len());
+    for (element, (drawn, expected)) in drawn.iter().zip(&expected).enumerate() {
+        assert_eq!(
+            drawn, expected,
+            "index {element} refers to the wrong vertex"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use yakui::widgets::Layer;
+use yakui_core::geometry::Color;
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::{VertexLayout, YakuiMiniquadState};
+
+use common::{drawn_positions, new_ui, painted_positions};
+
+#[test]
+fn every_layer_is_drawn_from_one_set_of_buffers() {
+    let mut ui = new_ui(640.0, 480.0);
+
+    // a box in the root layer, and a popup with text over it in a layer of its own
+    ui.start();
+    yakui::column(|| {
+        yakui::colored_box(Color::BLUE, [200.0, 24.0]);
+        Layer::new().show(|| {
+            yakui::text(16.0, "popup");
+        });
+    });
+    ui.finish();
+
+    let layers = ui.paint().layers();
+    assert_eq!(layers.len(), 2);
+    let box_indices = layers[0].calls[0].indices.len() as i32;
+    let text_indices = layers[1].calls[0].indices.len() as i32;
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+
+    let uploads: Vec<_> = backend
+        .commands()
+        .iter()
+        .filter(|command| matches!(command, RecordedCommand::BufferUpdate { .. }))
+        .collect();
+    assert_eq!(uploads.len(), 2, "one vertex and one index buffer");
+
+    let draws: Vec<_> = backend
+        .commands()
+        .iter()
+        .filter_map(|command| match command {
+            RecordedCommand::Draw {
+                base_element,
+                num_elements,
+                ..
+            } => Some((*base_element, *num_elements)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(draws, [(0, box_indices), (box_indices, text_indices)]);
+
+    // the indices of the popup are offset by the vertices of the root layer before it
+    assert_eq!(
+        drawn_positions(&backend, VertexLayout::Standard.vertex_size()),
+        painted_positions(&mut ui)
+    );
+}
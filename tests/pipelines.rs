@@ -0,0 +1,118 @@
+mod common;
+
+use miniquad::{BufferLayout, PipelineParams, ShaderMeta, ShaderSource, UniformBlockLayout};
+use yakui_core::geometry::Color;
+use yakui_core::paint::Pipeline;
+
+use yakui_miniquad::backend::{GraphicsBackend, RecordedCommand, RecordingBackend};
+use yakui_miniquad::{UnknownPipelineBehaviour, YakuiMiniquadState};
+
+use common::new_ui;
+
+/// Returns the pipelines and uniforms applied by painting a box and some text.
+fn paint(
+    backend: &mut RecordingBackend,
+    state: &mut YakuiMiniquadState<RecordingBackend>,
+) -> Vec<RecordedCommand> {
+    let mut ui = new_ui(640.0, 480.0);
+
+    ui.start();
+    yakui::column(|| {
+        yakui::colored_box(Color::BLUE, [100.0, 20.0]);
+        yakui::text(16.0, "hi");
+    });
+    ui.finish();
+
+    backend.take_commands();
+    state.paint(backend, &mut ui);
+    backend
+        .take_commands()
+        .into_iter()
+        .filter(|command| {
+            matches!(
+                command,
+                RecordedCommand::ApplyPipeline(_) | RecordedCommand::ApplyUniforms(_)
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn registered_pipelines_are_drawn_with_their_uniforms() {
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+
+    let shader = backend
+        .new_shader(
+            ShaderSource::Glsl {
+                vertex: "",
+                fragment: "",
+            },
+            ShaderMeta {
+                images: vec!["tex".to_string()],
+                uniforms: UniformBlockLayout { uniforms: vec![] },
+            },
+        )
+        .unwrap();
+    let custom = backend.new_pipeline(
+        &[BufferLayout::default()],
+        state.renderer().vertex_layout().attributes(),
+        shader,
+        PipelineParams::default(),
+    );
+    state.register_pipeline(Pipeline::Main, custom, &[1, 2, 3, 4]);
+
+    let srgb_output = RecordedCommand::ApplyUniforms(1.0f32.to_ne_bytes().to_vec());
+    assert_eq!(
+        paint(&mut backend, &mut state),
+        [
+            RecordedCommand::ApplyPipeline(custom),
+            RecordedCommand::ApplyUniforms(vec![1, 2, 3, 4]),
+            RecordedCommand::ApplyPipeline(1),
+            srgb_output.clone(),
+        ]
+    );
+
+    assert!(state.set_pipeline_uniforms(Pipeline::Main, &[5, 6]));
+    assert!(!state.set_pipeline_uniforms(Pipeline::Text, &[5, 6]));
+    assert_eq!(
+        paint(&mut backend, &mut state)[..2],
+        [
+            RecordedCommand::ApplyPipeline(custom),
+            RecordedCommand::ApplyUniforms(vec![5, 6]),
+        ]
+    );
+
+    // the built-in pipeline is used again once the custom one is gone
+    assert_eq!(state.unregister_pipeline(Pipeline::Main), Some(custom));
+    assert_eq!(
+        paint(&mut backend, &mut state)[..2],
+        [RecordedCommand::ApplyPipeline(0), srgb_output]
+    );
+}
+
+#[test]
+fn unknown_pipeline_behaviour_leaves_built_in_pipelines_alone() {
+    for behaviour in [
+        UnknownPipelineBehaviour::Skip,
+        UnknownPipelineBehaviour::DrawWithMain,
+        UnknownPipelineBehaviour::Error,
+    ] {
+        let mut backend = RecordingBackend::new();
+        let mut state = YakuiMiniquadState::new(&mut backend);
+        state.set_unknown_pipeline_behaviour(behaviour);
+
+        let pipelines: Vec<_> = paint(&mut backend, &mut state)
+            .into_iter()
+            .filter(|command| matches!(command, RecordedCommand::ApplyPipeline(_)))
+            .collect();
+        assert_eq!(
+            pipelines,
+            [
+                RecordedCommand::ApplyPipeline(0),
+                RecordedCommand::ApplyPipeline(1)
+            ],
+            "{behaviour:?}"
+        );
+    }
+}
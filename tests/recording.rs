@@ -0,0 +1,109 @@
+mod common;
+
+use miniquad::TextureFormat;
+use yakui_core::geometry::{Color, Constraints, Vec2};
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::YakuiMiniquadState;
+
+use common::new_ui;
+
+#[test]
+fn painting_records_textures_bindings_scissors_and_draws() {
+    let mut ui = new_ui(640.0, 480.0);
+
+    // a box, some text, and a box clipped by a scroll area
+    ui.start();
+    yakui::column(|| {
+        yakui::colored_box(Color::BLUE, [100.0, 20.0]);
+        yakui::text(16.0, "hi");
+        yakui::constrained(Constraints::tight(Vec2::new(100.0, 30.0)), || {
+            yakui::scroll_vertical(|| {
+                yakui::colored_box(Color::RED, [100.0, 100.0]);
+            });
+        });
+    });
+    ui.finish();
+
+    let calls: Vec<_> = ui
+        .paint()
+        .layers()
+        .iter()
+        .flat_map(|layer| &layer.calls)
+        .map(|call| call.indices.len() as i32)
+        .collect();
+    let [box_indices, text_indices, clipped_indices] = calls[..] else {
+        panic!("expected three calls, got {calls:?}");
+    };
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+    let commands = backend.commands();
+
+    // the renderer created the default white texture as texture 0, then yakui's glyph cache is uploaded
+    assert!(matches!(
+        commands[..2],
+        [
+            RecordedCommand::NewTexture {
+                texture: 1,
+                format: TextureFormat::Alpha,
+                ..
+            },
+            RecordedCommand::TextureUpdate(1),
+        ]
+    ));
+
+    // the geometry is uploaded before anything is drawn from it
+    let first_scissor = commands
+        .iter()
+        .position(|command| matches!(command, RecordedCommand::ApplyScissorRect { .. }))
+        .unwrap();
+    assert!(commands[..first_scissor]
+        .iter()
+        .any(|command| matches!(command, RecordedCommand::BufferUpdate { buffer: 1, .. })));
+
+    let srgb_output = RecordedCommand::ApplyUniforms(1.0f32.to_ne_bytes().to_vec());
+    let bindings = |texture| RecordedCommand::ApplyBindings {
+        vertex_buffers: vec![0],
+        index_buffer: 1,
+        textures: vec![texture],
+    };
+    let draw = |base_element, num_elements| RecordedCommand::Draw {
+        base_element,
+        num_elements,
+        num_instances: 1,
+    };
+
+    assert_eq!(
+        commands[first_scissor..],
+        [
+            RecordedCommand::ApplyScissorRect {
+                x: 0,
+                y: 0,
+                w: 640,
+                h: 480,
+            },
+            RecordedCommand::ApplyPipeline(0),
+            srgb_output.clone(),
+            bindings(0),
+            draw(0, box_indices),
+            RecordedCommand::ApplyPipeline(1),
+            srgb_output.clone(),
+            bindings(1),
+            draw(box_indices, text_indices),
+            // the scroll area is 30 pixels high at 38 pixels from the top, with the origin at the bottom
+            RecordedCommand::ApplyScissorRect {
+                x: 0,
+                y: 480 - 38 - 30,
+                w: 100,
+                h: 30,
+            },
+            RecordedCommand::ApplyPipeline(0),
+            srgb_output,
+            bindings(0),
+            draw(box_indices + text_indices, clipped_indices),
+        ]
+    );
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use yakui_core::geometry::Color;
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::YakuiMiniquadState;
+
+use common::{count, new_ui};
+
+#[test]
+fn render_target_is_reused_recreated_and_dropped() {
+    let mut ui = new_ui(320.0, 240.0);
+    ui.start();
+    yakui::colored_box(Color::RED, [100.0, 100.0]);
+    ui.finish();
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+
+    let first = state.paint_to_texture(&mut backend, &mut ui, 320, 240, None);
+    let commands = backend.take_commands();
+    assert!(commands.contains(&RecordedCommand::NewTexture {
+        texture: first,
+        format: miniquad::TextureFormat::RGBA8,
+        width: 320,
+        height: 240,
+    }));
+    assert!(commands.contains(&RecordedCommand::NewRenderPass {
+        pass: 0,
+        color_texture: first,
+    }));
+    assert!(commands.contains(&RecordedCommand::BeginPass {
+        pass: Some(0),
+        clear_color: None,
+    }));
+    assert_eq!(state.render_target(), Some(first));
+
+    // painting at the same size draws into the same target again
+    let clear_color = Some((0.0, 0.0, 0.0, 1.0));
+    assert_eq!(
+        state.paint_to_texture(&mut backend, &mut ui, 320, 240, clear_color),
+        first
+    );
+    let commands = backend.take_commands();
+    assert_eq!(
+        count(&commands, |command| matches!(
+            command,
+            RecordedCommand::NewTexture { .. } | RecordedCommand::NewRenderPass { .. }
+        )),
+        0
+    );
+    assert!(commands.contains(&RecordedCommand::BeginPass {
+        pass: Some(0),
+        clear_color,
+    }));
+
+    // a new size replaces the target, deleting the old one first
+    let resized = state.paint_to_texture(&mut backend, &mut ui, 640, 480, None);
+    assert_ne!(resized, first);
+    let commands = backend.take_commands();
+    assert_eq!(
+        commands[..4],
+        [
+            RecordedCommand::DeleteRenderPass(0),
+            RecordedCommand::DeleteTexture(first),
+            RecordedCommand::NewTexture {
+                texture: resized,
+                format: miniquad::TextureFormat::RGBA8,
+                width: 640,
+                height: 480,
+            },
+            RecordedCommand::NewRenderPass {
+                pass: 1,
+                color_texture: resized,
+            },
+        ]
+    );
+    assert!(commands.contains(&RecordedCommand::ApplyViewport {
+        x: 0,
+        y: 0,
+        w: 640,
+        h: 480,
+    }));
+
+    state.drop_render_target(&mut backend);
+    assert_eq!(
+        backend.take_commands(),
+        [
+            RecordedCommand::DeleteRenderPass(1),
+            RecordedCommand::DeleteTexture(resized),
+        ]
+    );
+    assert_eq!(state.render_target(), None);
+
+    // dropping it again does nothing
+    state.drop_render_target(&mut backend);
+    assert!(backend.commands().is_empty());
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use yakui_core::Yakui;
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::renderer::YakuiRenderer;
+use yakui_miniquad::YakuiMiniquadState;
+
+use common::{count, new_ui};
+
+fn hello(scale_factor: f32) -> Yakui {
+    let mut ui = new_ui(320.0, 240.0);
+    ui.set_scale_factor(scale_factor);
+
+    ui.start();
+    yakui::text(16.0, "hello, world!");
+    ui.finish();
+    ui
+}
+
+#[test]
+fn shared_renderer_creates_pipelines_once() {
+    let mut backend = RecordingBackend::new();
+    let renderer = YakuiRenderer::new(&mut backend);
+    let mut hud = YakuiMiniquadState::with_renderer(renderer.clone());
+    let mut overlay = YakuiMiniquadState::with_renderer(renderer);
+
+    let pipelines =
+        |command: &RecordedCommand| matches!(command, RecordedCommand::NewPipeline { .. });
+    assert_eq!(count(backend.commands(), pipelines), 2);
+
+    hud.paint(&mut backend, &mut hello(1.0));
+    overlay.paint(&mut backend, &mut hello(2.0));
+    assert_eq!(count(backend.commands(), pipelines), 2);
+}
+
+#[test]
+fn shared_renderer_keeps_textures_per_state() {
+    let mut backend = RecordingBackend::new();
+    let renderer = YakuiRenderer::new(&mut backend);
+    let mut hud = YakuiMiniquadState::with_renderer(renderer.clone());
+    let mut overlay = YakuiMiniquadState::with_renderer(renderer);
+
+    let textures =
+        |command: &RecordedCommand| matches!(command, RecordedCommand::NewTexture { .. });
+    let default_texture = count(backend.commands(), textures);
+
+    hud.paint(&mut backend, &mut hello(1.0));
+    let hud_textures = count(backend.commands(), textures) - default_texture;
+    assert!(hud_textures > 0);
+
+    overlay.paint(&mut backend, &mut hello(2.0));
+    assert_eq!(
+        count(backend.commands(), textures) - default_texture,
+        hud_textures * 2
+    );
+
+    // dropping the textures of one state leaves those of the other alone
+    backend.take_commands();
+    hud.drop_textures(&mut backend);
+    let deleted = |command: &RecordedCommand| matches!(command, RecordedCommand::DeleteTexture(_));
+    assert_eq!(count(backend.commands(), deleted), hud_textures);
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use miniquad::{KeyCode, KeyMods, MouseButton, TouchPhase};
+use yakui_core::geometry::{Constraints, Vec2};
+use yakui_core::input::{KeyCode as YakuiKeyCode, Modifiers};
+
+use yakui_miniquad::backend::{RecordedCommand, RecordingBackend};
+use yakui_miniquad::coordinates::CoordinateSpace;
+use yakui_miniquad::renderer::YakuiRenderer;
+use yakui_miniquad::stack::YakuiStack;
+use yakui_miniquad::YakuiMiniQuad;
+
+use common::{count, key_recorder, RecordedKeys};
+
+const HUD: usize = 0;
+const OVERLAY: usize = 1;
+
+/// Creates a stack of a HUD with an overlay on top of it, without a window.
+fn new_stack() -> YakuiStack<RecordingBackend> {
+    let mut stack = YakuiStack::new(&mut RecordingBackend::new());
+    for _ in [HUD, OVERLAY] {
+        let index = stack.push();
+        stack
+            .get(index)
+            .unwrap()
+            .set_window_space(Some(CoordinateSpace::new(Vec2::new(640.0, 480.0), 1.0)));
+    }
+    stack
+}
+
+fn cell(children: impl FnOnce()) {
+    yakui::constrained(Constraints::tight(Vec2::splat(100.0)), children);
+}
+
+/// Builds a frame of the HUD with a key recorder and a button in the top-left corner, and of the overlay with a button
+/// right on top of the one of the HUD and a key recorder next to it, all of them 100x100.
+///
+/// Returns whether the buttons of the HUD and the overlay were clicked since the previous frame.
+fn frame(
+    stack: &mut YakuiStack<RecordingBackend>,
+    hud_keys: &RecordedKeys,
+    overlay_keys: &RecordedKeys,
+) -> (bool, bool) {
+    let mut clicked = [false; 2];
+
+    let hud = stack.get(HUD).unwrap();
+    hud.start();
+    yakui::row(|| {
+        cell(|| key_recorder(hud_keys));
+        cell(|| clicked[HUD] = yakui::button("hud").clicked);
+    });
+    hud.finish();
+
+    let overlay = stack.get(OVERLAY).unwrap();
+    overlay.start();
+    yakui::row(|| {
+        cell(|| {});
+        cell(|| clicked[OVERLAY] = yakui::button("overlay").clicked);
+        cell(|| key_recorder(overlay_keys));
+    });
+    overlay.finish();
+
+    (clicked[HUD], clicked[OVERLAY])
+}
+
+fn click(stack: &mut YakuiStack<RecordingBackend>, x: f32, y: f32) {
+    stack.mouse_motion_event(x, y);
+    stack.mouse_button_down_event(MouseButton::Left, x, y);
+    stack.mouse_button_up_event(MouseButton::Left, x, y);
+}
+
+#[test]
+fn clicks_on_the_overlay_dont_reach_the_hud_below() {
+    let keys = [RecordedKeys::default(), RecordedKeys::default()];
+    let mut stack = new_stack();
+    frame(&mut stack, &keys[HUD], &keys[OVERLAY]);
+
+    click(&mut stack, 150.0, 50.0);
+    assert_eq!(frame(&mut stack, &keys[HUD], &keys[OVERLAY]), (false, true));
+
+    // only the overlay has the cursor over its ui
+    assert!(!stack.get(HUD).unwrap().is_cursor_over_ui());
+    assert!(stack.get(OVERLAY).unwrap().is_cursor_over_ui());
+
+    // touches go to the topmost widget under them too
+    stack.touch_event(TouchPhase::Started, 0, 150.0, 50.0);
+    stack.touch_event(TouchPhase::Ended, 0, 150.0, 50.0);
+    assert_eq!(frame(&mut stack, &keys[HUD], &keys[OVERLAY]), (false, true));
+}
+
+#[test]
+fn drags_stay_with_the_instance_they_started_in() {
+    let keys = [RecordedKeys::default(), RecordedKeys::default()];
+    let mut stack = new_stack();
+    frame(&mut stack, &keys[HUD], &keys[OVERLAY]);
+
+    // pressing on the key recorder of the HUD, and dragging over the button of the overlay
+    stack.mouse_motion_event(50.0, 50.0);
+    stack.mouse_button_down_event(MouseButton::Left, 50.0, 50.0);
+    stack.mouse_motion_event(150.0, 50.0);
+    frame(&mut stack, &keys[HUD], &keys[OVERLAY]);
+    assert!(stack.get(HUD).unwrap().input_capture().wants_pointer);
+    assert!(!stack.get(OVERLAY).unwrap().is_cursor_over_ui());
+
+    stack.mouse_button_up_event(MouseButton::Left, 150.0, 50.0);
+    assert_eq!(
+        frame(&mut stack, &keys[HUD], &keys[OVERLAY]),
+        (false, false)
+    );
+
+    // once released, the overlay gets the cursor back
+    stack.mouse_motion_event(150.0, 50.0);
+    assert!(stack.get(OVERLAY).unwrap().is_cursor_over_ui());
+}
+
+#[test]
+fn keys_go_to_the_topmost_instance_wanting_the_keyboard() {
+    let keys = [RecordedKeys::default(), RecordedKeys::default()];
+    let mut stack = new_stack();
+    frame(&mut stack, &keys[HUD], &keys[OVERLAY]);
+
+    // the HUD is focused with the mouse, and the overlay with a touch the HUD never sees
+    click(&mut stack, 50.0, 50.0);
+    stack.touch_event(TouchPhase::Started, 0, 250.0, 50.0);
+    stack.touch_event(TouchPhase::Ended, 0, 250.0, 50.0);
+    frame(&mut stack, &keys[HUD], &keys[OVERLAY]);
+    assert!(stack.get(HUD).unwrap().input_capture().wants_keyboard);
+    assert!(stack.get(OVERLAY).unwrap().input_capture().wants_keyboard);
+
+    stack.key_down_event(KeyCode::A, KeyMods::default(), false);
+    stack.key_up_event(KeyCode::A, KeyMods::default());
+
+    assert_eq!(
+        *keys[OVERLAY].borrow(),
+        [
+            (YakuiKeyCode::KeyA, true, Modifiers::empty()),
+            (YakuiKeyCode::KeyA, false, Modifiers::empty()),
+        ]
+    );
+    // releases reach every instance, so a key can't get stuck
+    assert_eq!(
+        *keys[HUD].borrow(),
+        [(YakuiKeyCode::KeyA, false, Modifiers::empty())]
+    );
+}
+
+#[test]
+fn stacks_can_share_a_renderer_with_other_instances() {
+    let mut backend = RecordingBackend::new();
+    let renderer = YakuiRenderer::new(&mut backend);
+    let mut stack = YakuiStack::with_renderer(renderer.clone());
+    let mut standalone = YakuiMiniQuad::with_renderer(renderer);
+    let index = stack.push();
+
+    let window = Some(CoordinateSpace::new(Vec2::new(640.0, 480.0), 1.0));
+    for instance in [stack.get(index).unwrap(), &mut standalone] {
+        instance.set_window_space(window);
+        instance.start();
+        yakui::text(16.0, "hello, world!");
+        instance.finish();
+    }
+    stack.draw(&mut backend);
+    standalone.draw(&mut backend);
+
+    let pipelines = count(backend.commands(), |command| {
+        matches!(command, RecordedCommand::NewPipeline { .. })
+    });
+    assert_eq!(pipelines, 2);
+}
+
+#[test]
+fn quitting_lets_go_of_the_instance_a_press_started_in() {
+    let keys = [RecordedKeys::default(), RecordedKeys::default()];
+    let mut stack = new_stack();
+    frame(&mut stack, &keys[HUD], &keys[OVERLAY]);
+
+    // pressing on the button of the overlay, whose release never arrives
+    stack.mouse_motion_event(150.0, 50.0);
+    stack.mouse_button_down_event(MouseButton::Left, 150.0, 50.0);
+    stack.quit_requested_event();
+
+    // the HUD below sees the cursor again, over its key recorder
+    stack.mouse_motion_event(50.0, 50.0);
+    assert!(stack.get(HUD).unwrap().is_cursor_over_ui());
+}
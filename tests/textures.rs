@@ -0,0 +1,448 @@
+mod common;
+
+use miniquad::{
+    FilterMode, MipmapFilterMode, TextureAccess, TextureFormat, TextureParams, TextureSource,
+    TextureWrap,
+};
+use yakui_core::geometry::UVec2;
+use yakui_core::paint::{AddressMode, Texture, TextureFilter, TextureFormat as YakuiTextureFormat};
+use yakui_core::widget::{PaintContext, Widget};
+use yakui_core::{ManagedTextureId, TextureId};
+
+use yakui_miniquad::backend::{GraphicsBackend, RecordedCommand, RecordingBackend};
+use yakui_miniquad::{TextureSampling, YakuiMiniquadState};
+
+use common::{count, new_ui};
+
+#[test]
+fn textures_which_cant_be_uploaded_get_a_placeholder() {
+    let mut ui = new_ui(320.0, 240.0);
+
+    // half the bytes a 4x4 RGBA texture needs
+    let texture = Texture::new(YakuiTextureFormat::Rgba8Srgb, UVec2::new(4, 4), vec![0; 32]);
+    ui.add_texture(texture);
+
+    ui.start();
+    ui.finish();
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+
+    assert!(
+        backend.commands().iter().any(|command| matches!(
+            command,
+            RecordedCommand::NewTexture {
+                format: TextureFormat::RGBA8,
+                width: 4,
+                height: 4,
+                ..
+            }
+        )),
+        "expected a placeholder texture in {:?}",
+        backend.commands()
+    );
+}
+
+/// Edits a texture of yakui while it's painted, the only place yakui lets widgets do so.
+#[derive(Debug)]
+struct EditTexture(Option<(ManagedTextureId, TextureEdit)>);
+
+#[derive(Debug, Clone, Copy)]
+enum TextureEdit {
+    Remove,
+    /// Replaces the pixels of the texture with this many bytes of white.
+    Modify(usize),
+    /// Replaces the texture with a white one of this size in the same format.
+    Resize(UVec2),
+}
+
+/// Shows an [`EditTexture`] which edits the texture with the given id.
+fn edit_texture(id: ManagedTextureId, edit: TextureEdit) {
+    yakui_core::context::dom().do_widget::<EditTexture>(Some((id, edit)));
+}
+
+impl Widget for EditTexture {
+    type Props<'a> = Option<(ManagedTextureId, TextureEdit)>;
+    type Response = ();
+
+    fn new() -> Self {
+        EditTexture(None)
+    }
+
+    fn update(&mut self, props: Self::Props<'_>) -> Self::Response {
+        self.0 = props;
+    }
+
+    fn paint(&self, ctx: PaintContext<'_>) {
+        match self.0 {
+            Some((id, TextureEdit::Remove)) => ctx.paint.remove_texture(id),
+            Some((id, TextureEdit::Modify(len))) => {
+                let texture = ctx.paint.texture_mut(id).unwrap();
+                *texture = Texture::new(texture.format(), texture.size(), vec![255; len]);
+                ctx.paint.mark_texture_modified(id);
+            }
+            Some((id, TextureEdit::Resize(size))) => {
+                let texture = ctx.paint.texture_mut(id).unwrap();
+                let texel_size = match texture.format() {
+                    YakuiTextureFormat::R8 => 1,
+                    _ => 4,
+                };
+                let len = size.x as usize * size.y as usize * texel_size;
+                *texture = Texture::new(texture.format(), size, vec![255; len]);
+                ctx.paint.mark_texture_modified(id);
+            }
+            None => {}
+        }
+    }
+}
+
+fn deleted_textures(commands: &[RecordedCommand]) -> Vec<usize> {
+    commands
+        .iter()
+        .filter_map(|command| match command {
+            RecordedCommand::DeleteTexture(texture) => Some(*texture),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn user_textures_are_never_deleted() {
+    let mut ui = new_ui(320.0, 240.0);
+    let [removed, kept] = [2, 3].map(|size| {
+        ui.add_texture(Texture::new(
+            YakuiTextureFormat::Rgba8Srgb,
+            UVec2::new(size, size),
+            vec![255; size as usize * size as usize * 4],
+        ))
+    });
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    let user = backend.new_texture(
+        TextureAccess::Static,
+        TextureSource::Bytes(&[255; 4]),
+        TextureParams {
+            width: 1,
+            height: 1,
+            ..Default::default()
+        },
+    );
+    let user_id = state.add_user_texture(user);
+
+    ui.start();
+    ui.finish();
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+    let uploaded = |size| {
+        backend
+            .commands()
+            .iter()
+            .find_map(|command| match command {
+                RecordedCommand::NewTexture { texture, width, .. } if *width == size => {
+                    Some(*texture)
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+    let [uploaded_removed, uploaded_kept] = [uploaded(2), uploaded(3)];
+
+    // removing a yakui texture only deletes what was uploaded for it
+    ui.start();
+    edit_texture(removed, TextureEdit::Remove);
+    ui.finish();
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+    assert_eq!(deleted_textures(backend.commands()), [uploaded_removed]);
+
+    backend.take_commands();
+    state.drop_textures(&mut backend);
+    assert_eq!(deleted_textures(backend.commands()), [uploaded_kept]);
+
+    // the user texture is handed back once, and only for its own id
+    assert_eq!(state.remove_user_texture(TextureId::Managed(kept)), None);
+    assert_eq!(state.remove_user_texture(user_id), Some(user));
+    assert_eq!(state.remove_user_texture(user_id), None);
+}
+
+#[test]
+fn texture_sampling_is_applied_to_uploaded_textures_unless_overridden() {
+    let mut ui = new_ui(320.0, 240.0);
+    let [_, overridden] = [2, 3].map(|size| {
+        ui.add_texture(Texture::new(
+            YakuiTextureFormat::Rgba8Srgb,
+            UVec2::new(size, size),
+            vec![255; size as usize * size as usize * 4],
+        ))
+    });
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    let user = backend.new_texture(
+        TextureAccess::Static,
+        TextureSource::Bytes(&[255; 4]),
+        TextureParams {
+            width: 1,
+            height: 1,
+            ..Default::default()
+        },
+    );
+    state.add_user_texture(user);
+
+    ui.start();
+    ui.finish();
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+    let uploaded = backend
+        .commands()
+        .iter()
+        .find_map(|command| match command {
+            RecordedCommand::NewTexture {
+                texture, width: 2, ..
+            } => Some(*texture),
+            _ => None,
+        })
+        .unwrap();
+
+    state.set_texture_sampling_override(
+        &mut backend,
+        TextureId::Managed(overridden),
+        Some(TextureSampling::LINEAR),
+    );
+    backend.take_commands();
+
+    let sampling = TextureSampling {
+        wrap: TextureWrap::Repeat,
+        ..TextureSampling::NEAREST
+    };
+    state.set_texture_sampling(&mut backend, Some(sampling));
+    assert_eq!(state.texture_sampling(), Some(sampling));
+    assert_eq!(
+        backend.take_commands(),
+        [
+            RecordedCommand::TextureSetMinFilter {
+                texture: uploaded,
+                filter: FilterMode::Nearest,
+                mipmap_filter: MipmapFilterMode::None,
+            },
+            RecordedCommand::TextureSetMagFilter {
+                texture: uploaded,
+                filter: FilterMode::Nearest,
+            },
+            RecordedCommand::TextureSetWrap {
+                texture: uploaded,
+                wrap_x: TextureWrap::Repeat,
+                wrap_y: TextureWrap::Repeat,
+            },
+        ]
+    );
+}
+
+/// Returns the filters and wrap mode the given texture is sampled with.
+fn sampling_of(
+    backend: &RecordingBackend,
+    texture: usize,
+) -> (FilterMode, FilterMode, TextureWrap) {
+    let params = backend.texture_params(texture);
+    (params.min_filter, params.mag_filter, params.wrap)
+}
+
+#[test]
+fn textures_are_sampled_like_their_yakui_texture_asks_unless_overridden() {
+    let mut ui = new_ui(320.0, 240.0);
+    let [smooth, _] = [2, 3].map(|size| {
+        let mut texture = Texture::new(
+            YakuiTextureFormat::Rgba8Srgb,
+            UVec2::new(size, size),
+            vec![255; size as usize * size as usize * 4],
+        );
+        // yakui minifies with the nearest texel by default
+        texture.min_filter = TextureFilter::Linear;
+        if size == 3 {
+            texture.min_filter = TextureFilter::Nearest;
+            texture.mag_filter = TextureFilter::Nearest;
+            texture.address_mode = AddressMode::Repeat;
+        }
+        ui.add_texture(texture)
+    });
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    ui.start();
+    ui.finish();
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+    let [smooth_uploaded, pixel_art_uploaded] = [2, 3].map(|size| {
+        backend
+            .commands()
+            .iter()
+            .find_map(|command| match command {
+                RecordedCommand::NewTexture { texture, width, .. } if *width == size => {
+                    Some(*texture)
+                }
+                _ => None,
+            })
+            .unwrap()
+    });
+    let smooth_sampling = (FilterMode::Linear, FilterMode::Linear, TextureWrap::Clamp);
+    let pixel_art_sampling = (
+        FilterMode::Nearest,
+        FilterMode::Nearest,
+        TextureWrap::Repeat,
+    );
+    assert_eq!(sampling_of(&backend, smooth_uploaded), smooth_sampling);
+    assert_eq!(
+        sampling_of(&backend, pixel_art_uploaded),
+        pixel_art_sampling
+    );
+
+    // sampling set for every texture wins over what they ask for
+    state.set_texture_sampling(&mut backend, Some(TextureSampling::LINEAR));
+    assert_eq!(sampling_of(&backend, pixel_art_uploaded), smooth_sampling);
+
+    // and sampling set for a single texture wins over that
+    state.set_texture_sampling_override(
+        &mut backend,
+        TextureId::Managed(smooth),
+        Some(TextureSampling::NEAREST),
+    );
+    let nearest = (FilterMode::Nearest, FilterMode::Nearest, TextureWrap::Clamp);
+    assert_eq!(sampling_of(&backend, smooth_uploaded), nearest);
+
+    state.set_texture_sampling(&mut backend, None);
+    assert_eq!(sampling_of(&backend, smooth_uploaded), nearest);
+    assert_eq!(
+        sampling_of(&backend, pixel_art_uploaded),
+        pixel_art_sampling
+    );
+
+    state.set_texture_sampling_override(&mut backend, TextureId::Managed(smooth), None);
+    assert_eq!(sampling_of(&backend, smooth_uploaded), smooth_sampling);
+}
+
+#[test]
+fn modified_placeholders_are_updated_once_they_can_be_uploaded() {
+    let mut ui = new_ui(320.0, 240.0);
+
+    // half the bytes a 4x4 RGBA texture needs
+    let id = ui.add_texture(Texture::new(
+        YakuiTextureFormat::Rgba8Srgb,
+        UVec2::new(4, 4),
+        vec![0; 32],
+    ));
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    ui.start();
+    ui.finish();
+    backend.take_commands();
+    state.paint(&mut backend, &mut ui);
+    let placeholder = backend
+        .commands()
+        .iter()
+        .find_map(|command| match command {
+            RecordedCommand::NewTexture { texture, .. } => Some(*texture),
+            _ => None,
+        })
+        .unwrap();
+
+    let mut paint_modified = |len| {
+        ui.start();
+        edit_texture(id, TextureEdit::Modify(len));
+        ui.finish();
+        backend.take_commands();
+        state.paint(&mut backend, &mut ui);
+        count(
+            backend.commands(),
+            |command| matches!(command, RecordedCommand::TextureUpdate(texture) if *texture == placeholder),
+        )
+    };
+
+    // still too short, so the placeholder stays
+    assert_eq!(paint_modified(48), 0);
+
+    // the placeholder is the same size and format, so it's updated in place
+    assert_eq!(paint_modified(64), 1);
+}
+
+#[test]
+fn modified_textures_are_created_again_once_their_format_or_size_changes() {
+    let mut ui = new_ui(320.0, 240.0);
+
+    // half the bytes a 4x4 single channel texture needs
+    let id = ui.add_texture(Texture::new(
+        YakuiTextureFormat::R8,
+        UVec2::new(4, 4),
+        vec![0; 8],
+    ));
+
+    let mut backend = RecordingBackend::new();
+    let mut state = YakuiMiniquadState::new(&mut backend);
+    backend.take_commands();
+
+    let mut paint_edited = |edit| {
+        ui.start();
+        if let Some(edit) = edit {
+            edit_texture(id, edit);
+        }
+        ui.finish();
+        state.paint(&mut backend, &mut ui);
+        backend
+            .take_commands()
+            .into_iter()
+            .filter(|command| {
+                matches!(
+                    command,
+                    RecordedCommand::NewTexture { .. }
+                        | RecordedCommand::TextureUpdate(_)
+                        | RecordedCommand::DeleteTexture(_)
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // the placeholder is always RGBA
+    let [RecordedCommand::NewTexture {
+        texture: placeholder,
+        format: TextureFormat::RGBA8,
+        ..
+    }] = paint_edited(None)[..]
+    else {
+        panic!("expected a placeholder");
+    };
+
+    // so the single channel data it gets once valid can't be uploaded into it
+    assert_eq!(
+        paint_edited(Some(TextureEdit::Modify(16))),
+        [
+            RecordedCommand::DeleteTexture(placeholder),
+            RecordedCommand::NewTexture {
+                texture: placeholder + 1,
+                format: TextureFormat::Alpha,
+                width: 4,
+                height: 4,
+            },
+        ]
+    );
+
+    assert_eq!(
+        paint_edited(Some(TextureEdit::Modify(16))),
+        [RecordedCommand::TextureUpdate(placeholder + 1)]
+    );
+
+    assert_eq!(
+        paint_edited(Some(TextureEdit::Resize(UVec2::new(8, 2)))),
+        [
+            RecordedCommand::DeleteTexture(placeholder + 1),
+            RecordedCommand::NewTexture {
+                texture: placeholder + 2,
+                format: TextureFormat::Alpha,
+                width: 8,
+                height: 2,
+            },
+        ]
+    );
+}